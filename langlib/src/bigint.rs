@@ -0,0 +1,394 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Div, Mul, Neg, Sub},
+    str::FromStr,
+};
+
+/// Every limb holds 9 decimal digits, which keeps printing cheap and still fits the product of two limbs in a `u64`.
+const BASE: u64 = 1_000_000_000;
+const BASE_DIGITS: usize = 9;
+
+/// An integer of any size.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    /// The magnitude of the number, least significant limb first. Zero has no limbs, and the last limb is never 0.
+    limbs: Vec<u32>,
+}
+
+impl BigInt {
+    /// Returns true if the number is 0.
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Returns true if the number is below 0.
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Converts the number into an `i32`, if it fits.
+    pub fn to_i32(&self) -> Option<i32> {
+        if self.limbs.len() > 2 {
+            return None;
+        }
+
+        let magnitude = self
+            .limbs
+            .iter()
+            .rev()
+            .fold(0i64, |acc, &limb| acc * BASE as i64 + limb as i64);
+
+        let n = if self.negative { -magnitude } else { magnitude };
+
+        i32::try_from(n).ok()
+    }
+
+    /// Builds a number from its sign and magnitude, and removes any leading zero limbs.
+    fn from_parts(negative: bool, mut limbs: Vec<u32>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+
+        Self {
+            // There's no such thing as -0
+            negative: negative && !limbs.is_empty(),
+            limbs,
+        }
+    }
+
+    /// Compares the magnitudes of two numbers, ignoring their signs.
+    fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+        a.len()
+            .cmp(&b.len())
+            .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+    }
+
+    fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut result = Vec::with_capacity(a.len().max(b.len()) + 1);
+        let mut carry = 0;
+
+        for i in 0..a.len().max(b.len()) {
+            let sum = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+
+            result.push((sum % BASE) as u32);
+            carry = sum / BASE;
+        }
+
+        if carry > 0 {
+            result.push(carry as u32);
+        }
+
+        result
+    }
+
+    /// Subtracts `b` from `a`. The magnitude of `a` must be at least that of `b`.
+    fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut result = Vec::with_capacity(a.len());
+        let mut borrow = 0;
+
+        for (i, &limb) in a.iter().enumerate() {
+            let mut diff = limb as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+
+            borrow = if diff < 0 {
+                diff += BASE as i64;
+                1
+            } else {
+                0
+            };
+
+            result.push(diff as u32);
+        }
+
+        result
+    }
+
+    fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut result = vec![0u64; a.len() + b.len()];
+
+        for (i, &x) in a.iter().enumerate() {
+            let mut carry = 0;
+
+            for (j, &y) in b.iter().enumerate() {
+                let product = result[i + j] + x as u64 * y as u64 + carry;
+
+                result[i + j] = product % BASE;
+                carry = product / BASE;
+            }
+
+            result[i + b.len()] += carry;
+        }
+
+        result.into_iter().map(|limb| limb as u32).collect()
+    }
+
+    /// Multiplies a magnitude by a single limb.
+    fn mul_small(a: &[u32], n: u32) -> Vec<u32> {
+        BigInt::mul_magnitude(a, &[n])
+    }
+
+    /// Divides `a` by `b` using long division, one limb at a time.
+    fn div_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut quotient = vec![0; a.len()];
+        let mut rem = BigInt::default();
+
+        for i in (0..a.len()).rev() {
+            // Shift the remainder up by a limb and bring down the next one
+            rem.limbs.insert(0, a[i]);
+            rem = BigInt::from_parts(false, rem.limbs);
+
+            // Binary search for the largest digit that `b` can be multiplied by without going past the remainder
+            let (mut lo, mut hi) = (0, BASE as u32 - 1);
+
+            while lo < hi {
+                let mid = lo + (hi - lo).div_ceil(2);
+
+                let product = BigInt::from_parts(false, BigInt::mul_small(b, mid));
+
+                if BigInt::cmp_magnitude(&product.limbs, &rem.limbs) == Ordering::Greater {
+                    hi = mid - 1;
+                } else {
+                    lo = mid;
+                }
+            }
+
+            if lo > 0 {
+                let product = BigInt::from_parts(false, BigInt::mul_small(b, lo));
+                rem = BigInt::from_parts(false, BigInt::sub_magnitude(&rem.limbs, &product.limbs));
+            }
+
+            quotient[i] = lo;
+        }
+
+        quotient
+    }
+}
+
+impl From<i32> for BigInt {
+    fn from(n: i32) -> Self {
+        BigInt::from(n as i64)
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(n: i64) -> Self {
+        let negative = n < 0;
+        let mut magnitude = n.unsigned_abs();
+        let mut limbs = Vec::new();
+
+        while magnitude > 0 {
+            limbs.push((magnitude % BASE) as u32);
+            magnitude /= BASE;
+        }
+
+        BigInt::from_parts(negative, limbs)
+    }
+}
+
+impl FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    /// Parses a number made of decimal digits, optionally starting with a `-`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s),
+        };
+
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+
+        // Chunk the digits into limbs, starting from the least significant end
+        let limbs = digits
+            .as_bytes()
+            .rchunks(BASE_DIGITS)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0, |acc, &digit| acc * 10 + (digit - b'0') as u32)
+            })
+            .collect();
+
+        Ok(BigInt::from_parts(negative, limbs))
+    }
+}
+
+impl Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+
+        match self.limbs.split_last() {
+            Some((first, rest)) => {
+                write!(f, "{first}")?;
+
+                // Every limb after the first one needs its leading zeros
+                for limb in rest.iter().rev() {
+                    write!(f, "{limb:0width$}", width = BASE_DIGITS)?;
+                }
+
+                Ok(())
+            }
+            None => write!(f, "0"),
+        }
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => BigInt::cmp_magnitude(&self.limbs, &other.limbs),
+            (true, true) => BigInt::cmp_magnitude(&other.limbs, &self.limbs),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.limbs.clone())
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(
+                self.negative,
+                BigInt::add_magnitude(&self.limbs, &other.limbs),
+            );
+        }
+
+        // The signs differ, so the smaller magnitude is taken away from the larger one, which decides the sign
+        match BigInt::cmp_magnitude(&self.limbs, &other.limbs) {
+            Ordering::Less => BigInt::from_parts(
+                other.negative,
+                BigInt::sub_magnitude(&other.limbs, &self.limbs),
+            ),
+            _ => BigInt::from_parts(
+                self.negative,
+                BigInt::sub_magnitude(&self.limbs, &other.limbs),
+            ),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != other.negative,
+            BigInt::mul_magnitude(&self.limbs, &other.limbs),
+        )
+    }
+}
+
+impl Div for &BigInt {
+    type Output = BigInt;
+
+    /// Divides two numbers, rounding towards zero like integer division does.
+    ///
+    /// # Panics
+    /// Panics if `other` is 0.
+    fn div(self, other: &BigInt) -> BigInt {
+        assert!(!other.is_zero(), "attempt to divide by zero");
+
+        BigInt::from_parts(
+            self.negative != other.negative,
+            BigInt::div_magnitude(&self.limbs, &other.limbs),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+#[error("The string is not a valid integer.")]
+pub struct ParseBigIntError;
+
+#[cfg(test)]
+mod bigint_tests {
+    use super::BigInt;
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_and_print() {
+        for s in [
+            "0",
+            "7",
+            "-42",
+            "1000000000",
+            "123456785435643829043568",
+            "-1000000000000000000001",
+        ] {
+            assert_eq!(big(s).to_string(), s);
+        }
+
+        assert_eq!(big("-0"), BigInt::default());
+        assert_eq!(big("000123").to_string(), "123");
+        assert!("12a".parse::<BigInt>().is_err());
+        assert!("".parse::<BigInt>().is_err());
+    }
+
+    #[test]
+    fn convert_i32() {
+        for n in [0, 1, -1, i32::MAX, i32::MIN] {
+            assert_eq!(BigInt::from(n).to_i32(), Some(n));
+            assert_eq!(BigInt::from(n).to_string(), n.to_string());
+        }
+
+        assert_eq!(big("2147483648").to_i32(), None);
+        assert_eq!(big("-2147483649").to_i32(), None);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = big("123456789012345678901234567890");
+        let b = big("-987654321098765432109876543210");
+
+        assert_eq!((&a + &b).to_string(), "-864197532086419753208641975320");
+        assert_eq!((&a - &b).to_string(), "1111111110111111111011111111100");
+        assert_eq!(
+            (&a * &b).to_string(),
+            "-121932631137021795226185032733622923332237463801111263526900"
+        );
+        assert_eq!((&b / &a).to_string(), "-8");
+        assert_eq!(
+            (&a / &big("1000000000")).to_string(),
+            "123456789012345678901"
+        );
+        assert_eq!((&a - &a), BigInt::default());
+    }
+
+    #[test]
+    fn compare() {
+        assert!(big("-5") < big("3"));
+        assert!(big("-50000000000") < big("-5"));
+        assert!(big("50000000000") > big("49999999999"));
+        assert_eq!(big("12").cmp(&big("12")), std::cmp::Ordering::Equal);
+    }
+}
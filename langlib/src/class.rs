@@ -0,0 +1,213 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ahash::AHashMap;
+
+use crate::{
+    expr::Var,
+    func::{Closure, Func},
+    interpreter::{self, err::RuntimeErr, Interpreter},
+    lexer::{op::BinOp, span::Span},
+    pattern::Binding,
+    value::Value,
+};
+
+/// A class declaration, as it appears in the code.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ClassDecl {
+    pub name: Binding,
+    /// The class this one inherits from, if there is one.
+    pub superclass: Option<Var>,
+    /// The traits the class promises to implement.
+    pub traits: Vec<String>,
+    pub methods: Vec<(String, Func)>,
+}
+
+impl ClassDecl {
+    pub fn new(
+        ident: &str,
+        superclass: Option<Var>,
+        traits: Vec<String>,
+        methods: Vec<(String, Func)>,
+    ) -> Self {
+        Self {
+            name: Binding::new(ident),
+            superclass,
+            traits,
+            methods,
+        }
+    }
+}
+
+/// A trait declaration, listing the methods a class has to define to implement it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TraitDecl {
+    pub ident: String,
+    /// The name of every required method, and how many arguments it has to accept.
+    pub methods: Vec<(String, usize)>,
+    /// Where the trait is named in the source.
+    pub span: Option<Span>,
+}
+
+impl TraitDecl {
+    pub fn new(ident: &str, methods: Vec<(String, usize)>) -> Self {
+        Self {
+            ident: ident.to_owned(),
+            methods,
+            span: None,
+        }
+    }
+}
+
+/// A class value, made by running a class declaration.
+#[derive(Debug)]
+pub struct Class {
+    pub name: String,
+    pub superclass: Option<Rc<Class>>,
+    traits: Vec<String>,
+    methods: AHashMap<String, Closure>,
+}
+
+// Classes are only equal to themselves.
+impl PartialEq for Class {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl Eq for Class {}
+
+impl Class {
+    pub fn new(
+        name: &str,
+        superclass: Option<Rc<Class>>,
+        traits: Vec<String>,
+        methods: AHashMap<String, Closure>,
+    ) -> Self {
+        Self {
+            name: name.to_owned(),
+            superclass,
+            traits,
+            methods,
+        }
+    }
+
+    /// Returns true if the class or one of its superclasses declares that it implements the trait. The resolver
+    /// has already checked that the methods match, so hosts can rely on this before calling into a script.
+    pub fn implements(&self, trait_name: &str) -> bool {
+        self.traits.iter().any(|ident| ident == trait_name)
+            || self
+                .superclass
+                .as_ref()
+                .is_some_and(|superclass| superclass.implements(trait_name))
+    }
+
+    /// Finds a method of the class, looking through its superclasses if the class doesn't define it itself.
+    pub fn find_method(&self, ident: &str) -> Option<&Closure> {
+        self.methods.get(ident).or_else(|| {
+            self.superclass
+                .as_ref()
+                .and_then(|superclass| superclass.find_method(ident))
+        })
+    }
+
+    /// Creates a new instance of the class, and runs its `init` method with the given arguments if it has one.
+    pub fn instantiate(
+        class: &Rc<Class>,
+        interpreter: &mut Interpreter,
+        args: Vec<Value>,
+        named: Vec<(String, Value)>,
+    ) -> Result<Value, interpreter::Err> {
+        let instance = Value::Instance(Rc::new(RefCell::new(Instance::new(Rc::clone(class)))));
+
+        match class.find_method("init") {
+            Some(init) => {
+                init.bind(instance.clone()).exec(interpreter, args, named)?;
+            }
+            None if !args.is_empty() || !named.is_empty() => {
+                return Err(RuntimeErr::BadArgLength(0, args.len() + named.len()).into())
+            }
+            None => {}
+        }
+
+        Ok(instance)
+    }
+}
+
+/// Returns the name of the method that instances use to overload `op`, if it can be overloaded. `!=` uses
+/// `__eq__` and negates the result.
+pub fn operator_method(op: &BinOp) -> Option<&'static str> {
+    match op {
+        BinOp::Add => Some("__add__"),
+        BinOp::Sub => Some("__sub__"),
+        BinOp::Mul => Some("__mul__"),
+        BinOp::Div => Some("__div__"),
+        BinOp::EqSign | BinOp::NeqSign => Some("__eq__"),
+        BinOp::LessSign => Some("__lt__"),
+        BinOp::LessEqSign => Some("__le__"),
+        BinOp::GreaterSign => Some("__gt__"),
+        BinOp::GreaterEqSign => Some("__ge__"),
+        BinOp::And | BinOp::Or | BinOp::In | BinOp::Coalesce => None,
+    }
+}
+
+/// Returns the name of the method that instances on the right of `op` use to overload it, if it can be
+/// overloaded. Comparisons are flipped (e.g `1 < v` calls `v.__gt__(1)`), `==` and `!=` use `__eq__` again, and
+/// arithmetic uses a method of its own (e.g `1 + v` calls `v.__radd__(1)`).
+pub fn reflected_operator_method(op: &BinOp) -> Option<&'static str> {
+    match op {
+        BinOp::Add => Some("__radd__"),
+        BinOp::Sub => Some("__rsub__"),
+        BinOp::Mul => Some("__rmul__"),
+        BinOp::Div => Some("__rdiv__"),
+        BinOp::EqSign | BinOp::NeqSign => Some("__eq__"),
+        BinOp::LessSign => Some("__gt__"),
+        BinOp::LessEqSign => Some("__ge__"),
+        BinOp::GreaterSign => Some("__lt__"),
+        BinOp::GreaterEqSign => Some("__le__"),
+        BinOp::And | BinOp::Or | BinOp::In | BinOp::Coalesce => None,
+    }
+}
+
+/// An instance of a class.
+#[derive(Debug)]
+pub struct Instance {
+    pub class: Rc<Class>,
+    fields: AHashMap<String, Value>,
+}
+
+// Instances are only equal to themselves. Instances are always compared through the `RefCell` they live in, so
+// comparing the addresses is enough.
+impl PartialEq for Instance {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl Eq for Instance {}
+
+impl Instance {
+    pub fn new(class: Rc<Class>) -> Self {
+        Self {
+            class,
+            fields: AHashMap::new(),
+        }
+    }
+
+    /// Gets a property of the instance. Fields shadow methods, and methods are bound to `this`, which has to be
+    /// the value holding the instance.
+    pub fn get(&self, ident: &str, this: &Value) -> Result<Value, RuntimeErr> {
+        if let Some(v) = self.fields.get(ident) {
+            return Ok(v.clone());
+        }
+
+        match self.class.find_method(ident) {
+            Some(method) => Ok(Value::Func(method.bind(this.clone()))),
+            None => Err(RuntimeErr::UndefinedProperty(ident.to_owned())),
+        }
+    }
+
+    /// Sets a field of the instance, creating it if it doesn't exist yet.
+    pub fn set(&mut self, ident: &str, v: Value) {
+        self.fields.insert(ident.to_owned(), v);
+    }
+}
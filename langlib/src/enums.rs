@@ -0,0 +1,168 @@
+use std::{fmt::Display, rc::Rc};
+
+use colored::Colorize;
+
+use crate::{interpreter::err::RuntimeErr, pattern::Binding, value::Value};
+
+/// An enum declaration, as it appears in the code.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct EnumDecl {
+    pub name: Binding,
+    pub variants: Vec<VariantDecl>,
+}
+
+impl EnumDecl {
+    pub fn new(ident: &str, variants: Vec<VariantDecl>) -> Self {
+        Self {
+            name: Binding::new(ident),
+            variants,
+        }
+    }
+}
+
+/// A variant of an enum, with the names of the values it holds (e.g `Rect(w, h)`).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VariantDecl {
+    pub ident: String,
+    pub fields: Vec<String>,
+}
+
+impl VariantDecl {
+    pub fn new(ident: &str, fields: &[&str]) -> Self {
+        Self {
+            ident: ident.to_owned(),
+            fields: fields.iter().map(|&field| field.to_owned()).collect(),
+        }
+    }
+}
+
+/// An enum value, made by running an enum declaration. Its variants are reached as properties
+/// (e.g `Shape.Circle(2)`, `Shape.Empty`).
+#[derive(Debug)]
+pub struct Enum {
+    pub name: String,
+    pub variants: Vec<VariantDecl>,
+}
+
+// Enums are only equal to themselves, so two enums with the same variants still can't be mixed up.
+impl PartialEq for Enum {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl Eq for Enum {}
+
+impl Enum {
+    pub fn new(decl: &EnumDecl) -> Self {
+        Self {
+            name: decl.name.ident.clone(),
+            variants: decl.variants.clone(),
+        }
+    }
+
+    /// Gets a variant of the enum. Variants without any values are returned as they are, and the others give a
+    /// constructor that has to be called with the values.
+    pub fn get(enum_val: &Rc<Enum>, ident: &str) -> Result<Value, RuntimeErr> {
+        let tag = enum_val
+            .variants
+            .iter()
+            .position(|variant| variant.ident == ident)
+            .ok_or_else(|| RuntimeErr::UndefinedProperty(ident.to_owned()))?;
+
+        let constructor = Constructor {
+            enum_val: Rc::clone(enum_val),
+            tag,
+        };
+
+        match enum_val.variants[tag].fields.len() {
+            0 => constructor.construct(Vec::new()),
+            _ => Ok(Value::Constructor(constructor)),
+        }
+    }
+}
+
+/// Makes values of one variant of an enum.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Constructor {
+    pub enum_val: Rc<Enum>,
+    /// The index of the variant in the enum.
+    pub tag: usize,
+}
+
+impl Constructor {
+    pub fn decl(&self) -> &VariantDecl {
+        &self.enum_val.variants[self.tag]
+    }
+
+    /// Makes a value of the variant, which has to be given exactly one value for every field.
+    pub fn construct(&self, values: Vec<Value>) -> Result<Value, RuntimeErr> {
+        let len = self.decl().fields.len();
+
+        if values.len() != len {
+            return Err(RuntimeErr::BadArgLength(len, values.len()));
+        }
+
+        Ok(Value::Variant(Rc::new(Variant {
+            enum_val: Rc::clone(&self.enum_val),
+            tag: self.tag,
+            values,
+        })))
+    }
+}
+
+/// A value of one of the variants of an enum. Variants can't be changed once they're made, so two of them are
+/// equal if they belong to the same variant and hold equal values.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Variant {
+    pub enum_val: Rc<Enum>,
+    pub tag: usize,
+    pub values: Vec<Value>,
+}
+
+impl Variant {
+    pub fn decl(&self) -> &VariantDecl {
+        &self.enum_val.variants[self.tag]
+    }
+
+    /// Gets one of the values of the variant by the name of its field.
+    pub fn get(&self, ident: &str) -> Result<Value, RuntimeErr> {
+        self.decl()
+            .fields
+            .iter()
+            .position(|field| field == ident)
+            .map(|i| self.values[i].clone())
+            .ok_or_else(|| RuntimeErr::UndefinedProperty(ident.to_owned()))
+    }
+
+    /// Writes the variant, passing on the lists and maps that are being written around it (see `Value::fmt_seen`).
+    pub(crate) fn fmt_seen(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        seen: &mut Vec<*const ()>,
+    ) -> std::fmt::Result {
+        write!(f, "{}", self.decl().ident.cyan())?;
+
+        if self.values.is_empty() {
+            return Ok(());
+        }
+
+        write!(f, "(")?;
+
+        for (i, v) in self.values.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+
+            v.fmt_seen(f, seen)?;
+        }
+
+        write!(f, ")")
+    }
+}
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_seen(f, &mut Vec::new())
+    }
+}
@@ -1,97 +1,119 @@
-use std::{fmt::Display, mem};
-
-use colored::Colorize;
-
-use crate::{func::Func, lexer::op::UnOp, parser::err::ParserError};
+use crate::{
+    bigint::BigInt,
+    func::Func,
+    interpreter::{config::Arithmetic, err::RuntimeErr},
+    lexer::{op::UnOp, span::Span},
+    pattern::Match,
+    value::Value,
+};
 
 use super::lexer::op::BinOp;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-
 pub enum Expr {
     Num(i32),
+    /// An integer literal too big for an `i32`.
+    BigNum(BigInt),
     Str(String),
-    Var(String),
+    Var(Var),
     Bool(bool),
     Bin(BinExpr),
-    Unary(UnOp, Box<Expr>),
-    Funcall(Box<Expr>, Vec<Expr>),
+    /// A unary operator applied to an operand, with where the whole expression is in the source.
+    Unary(UnOp, Box<Expr>, Option<Span>),
+    /// A function call with its positional arguments, followed by its named arguments (e.g `f(1, b: 2)`).
+    Funcall(Box<Expr>, Vec<Expr>, Vec<(String, Expr)>),
     Func(Func),
+    /// A list literal (e.g `[1, 2, 3]`).
+    List(Vec<Expr>),
+    /// A map literal, with its keys and values in order (e.g `{"a": 1}`).
+    Map(Vec<(Expr, Expr)>),
+    /// Indexing into a value (e.g `xs[i]`).
+    Index(Box<Expr>, Box<Expr>),
+    /// Getting a property of an instance (e.g `point.x`).
+    Get(Box<Expr>, String),
+    /// Getting a method of the superclass (e.g `super.init`). The variable holds the superclass, and `this` is
+    /// always stored one scope below it.
+    Super(Var, String),
+    /// Getting a property only if the value isn't null (e.g `user?.name`).
+    OptionalGet(Box<Expr>, String),
+    /// Calling a function only if it isn't null (e.g `callback?.(1)`).
+    OptionalCall(Box<Expr>, Vec<Expr>, Vec<(String, Expr)>),
+    /// A chain of property accesses, calls and indexes with at least one optional link (e.g `a?.b.c()`). If any
+    /// optional link finds null, the rest of the chain is skipped and the whole chain is null.
+    Chain(Box<Expr>),
+    /// A conditional expression, which only evaluates the branch that's picked (e.g `n > 0 ? n : -n`).
+    Ternary(Box<Expr>, Box<Expr>, Box<Expr>),
+    /// A `match` used as an expression, which evaluates to the body of the arm that matched.
+    Match(Box<Match<Expr>>),
+    /// A range of integers, which includes its end if the flag is set (e.g `0..10` or `0..=10`).
+    Range(Box<Expr>, Box<Expr>, bool),
+    /// Slicing a value, where either bound can be left out (e.g `xs[1:3]` or `xs[:2]`).
+    Slice(Box<Expr>, Option<Box<Expr>>, Option<Box<Expr>>),
     Null,
 }
 
 impl Expr {
-    pub fn eval(&self) -> Result<Expr, ParserError> {
+    /// Evaluates the expression without an interpreter. This only works for constant expressions, since there is no
+    /// environment to look variables up in.
+    pub fn eval(&self) -> Result<Value, RuntimeErr> {
         match self {
+            Expr::Num(num) => Ok(Value::Int(*num)),
+            Expr::BigNum(num) => Ok(Value::BigInt(num.clone())),
+            Expr::Str(string) => Ok(Value::Str(string.clone())),
+            Expr::Bool(bool) => Ok(Value::Bool(*bool)),
+            Expr::Null => Ok(Value::Null),
             Expr::Bin(expr) => expr.eval(),
-
-            Expr::Unary(op, expr) => {
-                if mem::discriminant(&Expr::Bool(false)) != mem::discriminant(&expr.eval()?) {
-                    return Err(ParserError::ExprError(ExprError::InvalidUnaryOperation));
-                }
-
-                let result: bool = expr.eval()?.try_into()?;
-
-                if op != &UnOp::Bang {
-                    return Err(ParserError::ExprError(ExprError::InvalidUnaryOperation));
-                }
-
-                Ok(Expr::Bool(!result))
-            }
-            _ => Ok(self.to_owned()),
+            Expr::Unary(op, expr, span) => Expr::apply_unary(op, expr.eval()?, span),
+            _ => Err(RuntimeErr::NotConstant),
         }
     }
-}
 
-impl Display for Expr {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Expr::Num(num) => write!(f, "{}", format!("{num}").yellow()),
-
-            Expr::Str(string) => write!(f, "{string}"),
-
-            Expr::Bool(bool) => write!(f, "{}", format!("{bool}").yellow()),
-            Expr::Null => write!(f, "{}", "null".bright_black()),
-            Expr::Func(_) => write!(f, "{}", "<func>".bright_black()),
-            other => write!(f, "{other:?}"),
+    /// Applies a unary operator to a value. The span is only used for error messages.
+    pub fn apply_unary(op: &UnOp, val: Value, span: &Option<Span>) -> Result<Value, RuntimeErr> {
+        match (op, val) {
+            (UnOp::Bang, Value::Bool(bool)) => Ok(Value::Bool(!bool)),
+            // Negating the smallest `i32` doesn't fit, so it's promoted
+            (UnOp::Minus, Value::Int(num)) => match num.checked_neg() {
+                Some(num) => Ok(Value::Int(num)),
+                None => Ok(Value::from(-&BigInt::from(num))),
+            },
+            (UnOp::Minus, Value::BigInt(num)) => Ok(Value::from(-&num)),
+            (op, val) => Err(RuntimeErr::InvalidOperand(op.clone(), val.type_of(), *span)),
         }
     }
 }
 
-impl TryInto<i32> for Expr {
-    type Error = ParserError;
-
-    fn try_into(self) -> Result<i32, Self::Error> {
-        match self {
-            Expr::Num(num) => Ok(num),
-            _ => Err(ParserError::ExprError(ExprError::FailedConversion)),
-        }
-    }
+/// A reference to a variable.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Var {
+    pub ident: String,
+    /// Where the variable lives. This is filled in by the resolver, and stays `None` for globals.
+    pub slot: Option<Slot>,
+    /// Where the name is in the source, if the parser was given spans.
+    pub span: Option<Span>,
 }
 
-impl TryInto<bool> for Expr {
-    type Error = ParserError;
+impl Var {
+    pub fn new(ident: &str) -> Self {
+        Self::with_span(ident, None)
+    }
 
-    fn try_into(self) -> Result<bool, Self::Error> {
-        match self {
-            Expr::Bool(bool) => Ok(bool),
-            Expr::Num(num) => Ok(num > 0),
-            Expr::Str(s) => Ok(!s.is_empty()),
-            Expr::Null => Ok(false),
-            _ => Err(ParserError::ExprError(ExprError::FailedConversion)),
+    pub fn with_span(ident: &str, span: Option<Span>) -> Self {
+        Self {
+            ident: ident.to_owned(),
+            slot: None,
+            span,
         }
     }
 }
 
-impl TryInto<String> for Expr {
-    type Error = ParserError;
-
-    fn try_into(self) -> Result<String, Self::Error> {
-        match self {
-            Expr::Str(s) => Ok(s),
-            _ => Err(ParserError::ExprError(ExprError::FailedConversion)),
-        }
-    }
+/// The location of a local variable.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Slot {
+    /// The number of scopes between the reference and the variable's declaration.
+    pub depth: usize,
+    /// The index of the variable within the scope it was declared in.
+    pub index: usize,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -99,111 +121,136 @@ pub struct BinExpr {
     pub lhs: Box<Expr>,
     pub rhs: Box<Expr>,
     pub op: BinOp,
+    /// Where the expression is in the source, if the parser was given spans.
+    pub span: Option<Span>,
 }
 
 impl BinExpr {
     pub fn new(lhs: Box<Expr>, rhs: Box<Expr>, op: BinOp) -> Self {
-        Self { lhs, rhs, op }
+        Self {
+            lhs,
+            rhs,
+            op,
+            span: None,
+        }
     }
 
-    /// Attempts to convert the operands into numbers.
-    fn try_into_nums(&self) -> Result<(i32, i32), ParserError> {
-        let lhs: i32 = (*self.lhs).eval()?.try_into()?;
-
-        let rhs: i32 = (*self.rhs).eval()?.try_into()?;
-
-        Ok((lhs, rhs))
+    /// Evaluates the expression without an interpreter, as long as both operands are constant. Arithmetic uses
+    /// the default mode.
+    pub fn eval(&self) -> Result<Value, RuntimeErr> {
+        self.apply(self.lhs.eval()?, self.rhs.eval()?, Arithmetic::default())
     }
 
-    /// Attempts to convert the operands into strings.
-    fn try_into_strings(&self) -> Result<(String, String), ParserError> {
-        let a: String = (*self.lhs).eval()?.try_into()?;
+    /// Applies the operator of the expression to two values, handling integer overflow as `arithmetic` says.
+    pub fn apply(
+        &self,
+        lhs: Value,
+        rhs: Value,
+        arithmetic: Arithmetic,
+    ) -> Result<Value, RuntimeErr> {
+        // Big integers are much slower, so they're only used when one of the operands already is one
+        let big = match (&lhs, &rhs) {
+            (Value::BigInt(a), Value::BigInt(b)) => Some((a.clone(), b.clone())),
+            (Value::BigInt(a), Value::Int(b)) => Some((a.clone(), BigInt::from(*b))),
+            (Value::Int(a), Value::BigInt(b)) => Some((BigInt::from(*a), b.clone())),
+            _ => None,
+        };
+
+        if let Some((a, b)) = big {
+            if let Some(result) = self.apply_big(&a, &b)? {
+                return Ok(result);
+            }
+        }
 
-        let b: String = (*self.rhs).eval()?.try_into()?;
+        let result = match (&self.op, lhs, rhs) {
+            (BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div, Value::Int(a), Value::Int(b)) => {
+                self.apply_int(a, b, arithmetic)?
+            }
+            (BinOp::Add, Value::Str(a), Value::Str(b)) => Value::Str(format!("{a}{b}")),
+            (BinOp::Coalesce, Value::Null, b) => b,
+            (BinOp::Coalesce, a, _) => a,
+            (BinOp::EqSign, a, b) => Value::Bool(a == b),
+            (BinOp::In, a, b) => Value::Bool(b.contains(&a)?),
+            (BinOp::NeqSign, a, b) => Value::Bool(a != b),
+            (BinOp::GreaterSign, Value::Int(a), Value::Int(b)) => Value::Bool(a > b),
+            (BinOp::LessSign, Value::Int(a), Value::Int(b)) => Value::Bool(a < b),
+            (BinOp::GreaterEqSign, Value::Int(a), Value::Int(b)) => Value::Bool(a >= b),
+            (BinOp::LessEqSign, Value::Int(a), Value::Int(b)) => Value::Bool(a <= b),
+            (BinOp::And, a, b) => match (a.truthy(), b.truthy()) {
+                (Some(a), Some(b)) => Value::Bool(a && b),
+                _ => return Err(self.invalid_operands(&a, &b)),
+            },
+            (BinOp::Or, a, b) => match (a.truthy(), b.truthy()) {
+                (Some(a), Some(b)) => Value::Bool(a || b),
+                _ => return Err(self.invalid_operands(&a, &b)),
+            },
+            (_, a, b) => return Err(self.invalid_operands(&a, &b)),
+        };
 
-        Ok((a, b))
+        Ok(result)
     }
 
-    /// Attempts to convert the operands into booleans.
-    fn try_into_bools(&self) -> Result<(bool, bool), ParserError> {
-        let lhs: bool = (*self.lhs).eval()?.try_into()?;
-
-        let rhs: bool = (*self.rhs).eval()?.try_into()?;
+    /// Applies an arithmetic operator to two integers.
+    fn apply_int(&self, a: i32, b: i32, arithmetic: Arithmetic) -> Result<Value, RuntimeErr> {
+        type Op<T> = fn(i32, i32) -> T;
+
+        let (checked, wrapping, saturating): (Op<Option<i32>>, Op<i32>, Op<i32>) = match self.op {
+            BinOp::Add => (i32::checked_add, i32::wrapping_add, i32::saturating_add),
+            BinOp::Sub => (i32::checked_sub, i32::wrapping_sub, i32::saturating_sub),
+            BinOp::Mul => (i32::checked_mul, i32::wrapping_mul, i32::saturating_mul),
+            BinOp::Div => (i32::checked_div, i32::wrapping_div, i32::saturating_div),
+            _ => unreachable!("only called for arithmetic operators"),
+        };
+
+        // Dividing by zero has no sensible result in any mode
+        if self.op == BinOp::Div && b == 0 {
+            return Err(RuntimeErr::DivisionByZero(self.span));
+        }
 
-        Ok((lhs, rhs))
-    }
+        let result = match arithmetic {
+            Arithmetic::Promoting => match checked(a, b) {
+                Some(result) => result,
+                None => {
+                    let result = self.apply_big(&BigInt::from(a), &BigInt::from(b))?;
 
-    /// Evaluates the expression, and consumes itself.
-    pub fn eval(&self) -> Result<Expr, ParserError> {
-        match self.op {
-            BinOp::Add => match self.try_into_nums() {
-                Ok((a, b)) => Ok(Expr::Num(a + b)),
-                Err(_) => match self.try_into_strings() {
-                    Ok((a, b)) => Ok(Expr::Str(format!("{a}{b}"))),
-                    Err(err) => Err(err),
-                },
+                    return Ok(result.expect("arithmetic operators always apply to big integers"));
+                }
             },
-            BinOp::Sub => {
-                let (lhs, rhs) = self.try_into_nums()?;
-
-                Ok(Expr::Num(lhs - rhs))
-            }
-            BinOp::Mul => {
-                let (lhs, rhs) = self.try_into_nums()?;
-
-                Ok(Expr::Num(lhs * rhs))
+            Arithmetic::Checked => {
+                checked(a, b).ok_or_else(|| RuntimeErr::Overflow(self.op.clone(), self.span))?
             }
-            BinOp::Div => {
-                let (lhs, rhs) = self.try_into_nums()?;
+            Arithmetic::Wrapping => wrapping(a, b),
+            Arithmetic::Saturating => saturating(a, b),
+        };
 
-                Ok(Expr::Num(lhs / rhs))
-            }
-            BinOp::EqSign => Ok(Expr::Bool(self.lhs.eval()? == self.rhs.eval()?)),
-            BinOp::GreaterSign => {
-                let (lhs, rhs) = self.try_into_nums()?;
-
-                Ok(Expr::Bool(lhs > rhs))
-            }
-            BinOp::LessSign => {
-                let (lhs, rhs) = self.try_into_nums()?;
-
-                Ok(Expr::Bool(lhs < rhs))
-            }
-            BinOp::GreaterEqSign => {
-                let (lhs, rhs) = self.try_into_nums()?;
-
-                Ok(Expr::Bool(lhs >= rhs))
-            }
-            BinOp::LessEqSign => {
-                let (lhs, rhs) = self.try_into_nums()?;
+        Ok(Value::Int(result))
+    }
 
-                Ok(Expr::Bool(lhs <= rhs))
-            }
-            BinOp::And => {
-                let (lhs, rhs) = self.try_into_bools()?;
-                Ok(Expr::Bool(lhs && rhs))
-            }
-            BinOp::Or => {
-                let (lhs, rhs) = self.try_into_bools()?;
+    /// Applies an arithmetic or comparison operator to two big integers, or returns `None` for any other operator.
+    fn apply_big(&self, a: &BigInt, b: &BigInt) -> Result<Option<Value>, RuntimeErr> {
+        let result = match self.op {
+            BinOp::Add => Value::from(a + b),
+            BinOp::Sub => Value::from(a - b),
+            BinOp::Mul => Value::from(a * b),
+            BinOp::Div if b.is_zero() => return Err(RuntimeErr::DivisionByZero(self.span)),
+            BinOp::Div => Value::from(a / b),
+            BinOp::GreaterSign => Value::Bool(a > b),
+            BinOp::LessSign => Value::Bool(a < b),
+            BinOp::GreaterEqSign => Value::Bool(a >= b),
+            BinOp::LessEqSign => Value::Bool(a <= b),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(result))
+    }
 
-                Ok(Expr::Bool(lhs || rhs))
-            }
-            BinOp::NeqSign => Ok(Expr::Bool(self.lhs.eval()? != self.rhs.eval()?)),
+    /// Makes the error for when the operator can't be applied to the given operands.
+    fn invalid_operands(&self, lhs: &Value, rhs: &Value) -> RuntimeErr {
+        RuntimeErr::InvalidOperands {
+            op: self.op.clone(),
+            lhs: lhs.type_of(),
+            rhs: rhs.type_of(),
+            span: self.span,
         }
     }
 }
-
-#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
-pub enum ExprError {
-    #[error("A failed conversion occured.")]
-    FailedConversion,
-
-    #[error("The parser failed to evaluate a binary expression")]
-    FailedBinEvaluation,
-
-    #[error("The parser failed to evaluate a unary expression")]
-    InvalidUnaryOperation,
-
-    #[error("The parser failed to compare two values.")]
-    InvalidComparision,
-}
@@ -1,90 +1,229 @@
+use std::{cell::RefCell, fmt::Debug, rc::Rc};
+
 use crate::{
-    expr::Expr,
-    interpreter::{self, env::Env, Interpreter},
+    expr::{Expr, Slot},
+    interpreter::{self, env::Env, err::RuntimeErr, flow::Flow, Interpreter},
+    lexer::span::Span,
     stmt::Stmt,
+    value::Value,
 };
+
+/// A function literal, as it appears in the code.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Func {
-    pub instructions: Box<Stmt>,
-    pub args: Vec<String>,
-    closure: Env,
+    /// The parameters and body of the function. These are shared with every closure created from the function, so
+    /// passing a function around never copies its code.
+    pub decl: Rc<FuncDecl>,
 }
 
-impl Func {
-    pub fn new(instructions: Stmt, args: Vec<String>) -> Self {
+/// The parameters and body of a function.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FuncDecl {
+    pub instructions: Stmt,
+    pub args: Vec<Param>,
+    /// The name of the trailing `...rest` parameter, if there is one.
+    pub rest: Option<String>,
+}
+
+/// A function value, made by evaluating a `Func` in some environment.
+#[derive(Clone)]
+pub struct Closure {
+    pub decl: Rc<FuncDecl>,
+    /// The environment the function was created in. It's shared with the enclosing scope, so captured variables
+    /// live on between calls.
+    env: Rc<RefCell<Env>>,
+    /// Whether the closure is the `init` method of a class, which always returns the instance it's bound to.
+    is_init: bool,
+}
+
+// The environment is skipped, since it usually holds the closure itself.
+impl Debug for Closure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Closure")
+            .field("decl", &self.decl)
+            .field("is_init", &self.is_init)
+            .finish_non_exhaustive()
+    }
+}
+
+// Two closures are only equal if they are the same function created in the same environment.
+impl PartialEq for Closure {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.decl, &other.decl) && Rc::ptr_eq(&self.env, &other.env)
+    }
+}
+
+impl Eq for Closure {}
+
+/// A single named parameter of a function, with an optional default value.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Param {
+    pub ident: String,
+    pub default: Option<Expr>,
+    /// Where the parameter is named in the source.
+    pub span: Option<Span>,
+}
+
+impl Param {
+    pub fn new(ident: &str, default: Option<Expr>) -> Self {
         Self {
-            instructions: Box::new(instructions),
-            args,
-            closure: Env::default(),
+            ident: ident.to_owned(),
+            default,
+            span: None,
         }
     }
+}
 
-    pub fn arg_len(&self) -> usize {
-        self.args.len()
+impl Func {
+    pub fn new(instructions: Stmt, args: Vec<Param>, rest: Option<String>) -> Self {
+        Self {
+            decl: Rc::new(FuncDecl {
+                instructions,
+                args,
+                rest,
+            }),
+        }
     }
+}
 
-    pub fn exec(
-        self,
-        interpreter: &mut Interpreter,
-        args: Vec<Expr>,
-    ) -> Result<Expr, interpreter::Err> {
+impl FuncDecl {
+    /// Returns the minimum number of arguments the function accepts.
+    pub fn min_args(&self) -> usize {
+        self.args.iter().filter(|arg| arg.default.is_none()).count()
+    }
 
+    /// Returns the maximum number of arguments the function accepts, or `None` if it takes a rest parameter.
+    pub fn max_args(&self) -> Option<usize> {
+        match self.rest {
+            Some(_) => None,
+            None => Some(self.args.len()),
+        }
+    }
 
+    /// Checks if the function can be called with `len` arguments.
+    pub fn check_arity(&self, len: usize) -> Result<(), RuntimeErr> {
+        let min = self.min_args();
 
-        let prev = self.closure;
+        match self.max_args() {
+            Some(max) if min == max && len != max => Err(RuntimeErr::BadArgLength(max, len)),
+            Some(max) if len > max => Err(RuntimeErr::TooManyArgs(max, len)),
+            _ if len < min => Err(RuntimeErr::TooFewArgs(min, len)),
+            _ => Ok(()),
+        }
+    }
 
-        println!("CURRENT CKOUSRE {:?}", prev);
+    /// Checks that a call with `len` positional arguments and the given named arguments can be bound to the
+    /// function's parameters.
+    fn check_named_args(&self, len: usize, named: &[(String, Value)]) -> Result<(), RuntimeErr> {
+        if let Some(max) = self.max_args() {
+            if len > max {
+                return Err(RuntimeErr::TooManyArgs(max, len));
+            }
+        }
 
+        for (i, (ident, _)) in named.iter().enumerate() {
+            let pos = match self.args.iter().position(|arg| &arg.ident == ident) {
+                Some(pos) => pos,
+                None => return Err(RuntimeErr::UnknownNamedArg(ident.clone())),
+            };
 
-        let mut new_env = Env::default();
-        new_env.set_parent(prev);
+            // The parameter was either already given positionally, or named twice
+            if pos < len || named[..i].iter().any(|(prev, _)| prev == ident) {
+                return Err(RuntimeErr::DuplicateArg(ident.clone()));
+            }
+        }
 
-        interpreter.env.replace(new_env);
+        Ok(())
+    }
+}
 
-        // Bring all the variables into scope
-        (0..args.len()).for_each(|i| {
-            interpreter.define_var(self.args[i].clone(), args[i].clone());
-        });
+impl Closure {
+    /// Creates a closure over `env`.
+    pub fn new(func: &Func, env: Rc<RefCell<Env>>) -> Self {
+        Self {
+            decl: Rc::clone(&func.decl),
+            env,
+            is_init: false,
+        }
+    }
 
-        // Get the instructions for the function
-        let stmts = match self.instructions.as_ref() {
-            Stmt::Block(stmts) => stmts,
-            _ => todo!(),
-        };
+    /// Creates a closure over `env` for the method `ident` of a class.
+    pub fn method(ident: &str, func: &Func, env: Rc<RefCell<Env>>) -> Self {
+        Self {
+            is_init: ident == "init",
+            ..Self::new(func, env)
+        }
+    }
 
-        // Keep executing until we encounter a return statement
-        let return_val = match stmts
-            .iter()
-            .find_map(|stmt| match interpreter.execute_stmt(stmt) {
-                Ok(_) => None,
-                Err(err) => match err {
-                    interpreter::Err::ReturnStmt(expr) => Some(Ok(expr)),
-                    err => Some(Err(err)),
-                },
-            }) {
-            Some(return_val) => Ok(return_val?),
-            None => Ok(Expr::Null),
-        };
+    /// Binds the closure to an instance, so that `this` refers to it inside the body. The instance is stored in
+    /// a scope of its own between the closure's environment and the function's parameters.
+    pub fn bind(&self, this: Value) -> Self {
+        let mut env = Env::new_enclosed(Rc::clone(&self.env));
+        env.define(0, this);
 
-        // Clean up the environment
-        (0..args.len()).for_each(|i| {
-            interpreter.env.borrow_mut().drop(&self.args[i]);
-        });
-
-        let parent = interpreter
-            .env
-            .to_owned()
-            .into_inner()
-            .parent
-            .unwrap()
-            .into_inner();
-        interpreter.env.replace(parent);
-
-        return_val
+        Self {
+            decl: Rc::clone(&self.decl),
+            env: Rc::new(RefCell::new(env)),
+            is_init: self.is_init,
+        }
     }
 
-    /// Sets the closure of this function.
-    pub fn set_closure(&mut self, closure: Env) {
-        self.closure = closure;
+    pub fn exec(
+        self,
+        interpreter: &mut Interpreter,
+        args: Vec<Value>,
+        named: Vec<(String, Value)>,
+    ) -> Result<Value, interpreter::Err> {
+        if named.is_empty() {
+            self.decl.check_arity(args.len())?;
+        } else {
+            self.decl.check_named_args(args.len(), &named)?;
+        }
+
+        // A bound `init` returns its instance, which `bind` stored in the closure's own scope
+        let this = match self.is_init {
+            true => Some(Env::get_at(&self.env, Slot { depth: 0, index: 0 }, "this")?),
+            false => None,
+        };
+
+        interpreter.scoped(Env::new_enclosed(self.env), |interpreter| {
+            // Bring all the variables into scope, in the same slots the resolver gave them. Parameters that weren't
+            // supplied fall back to their defaults, which are evaluated inside the function's scope so they can refer
+            // to earlier parameters.
+            for (i, param) in self.decl.args.iter().enumerate() {
+                let named_arg = named.iter().find(|(ident, _)| ident == &param.ident);
+
+                let val = match (args.get(i), named_arg, &param.default) {
+                    (Some(arg), _, _) => arg.clone(),
+                    (None, Some((_, arg)), _) => arg.clone(),
+                    (None, None, Some(default)) => interpreter.visit_expr(default)?,
+                    (None, None, None) => {
+                        return Err(RuntimeErr::MissingArg(param.ident.clone()).into())
+                    }
+                };
+
+                interpreter.define_var(i, val);
+            }
+
+            // Collect the remaining arguments into the rest parameter
+            if self.decl.rest.is_some() {
+                let extra = args.iter().skip(self.decl.args.len()).cloned().collect();
+
+                interpreter.define_var(self.decl.args.len(), Value::list(extra));
+            }
+
+            // The body shares the scope of the parameters
+            let flow = match &self.decl.instructions {
+                Stmt::Block(stmts) => interpreter.execute_stmts(stmts)?,
+                stmt => interpreter.execute_stmt(stmt)?,
+            };
+
+            // Running off the end of the function returns null
+            match (flow, this) {
+                (_, Some(this)) => Ok(this),
+                (Flow::Return(val), None) => Ok(val),
+                _ => Ok(Value::Null),
+            }
+        })
     }
 }
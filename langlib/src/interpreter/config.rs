@@ -0,0 +1,32 @@
+/// Options the embedder can use to change how the interpreter behaves.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Config {
+    /// What happens when integer arithmetic overflows.
+    pub arithmetic: Arithmetic,
+    /// How deeply calls can nest before the interpreter gives up with a stack overflow error, instead of running
+    /// out of native stack.
+    pub max_call_depth: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            arithmetic: Arithmetic::default(),
+            max_call_depth: 1000,
+        }
+    }
+}
+
+/// How integer arithmetic handles overflow.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Arithmetic {
+    /// Results that don't fit are promoted to big integers.
+    #[default]
+    Promoting,
+    /// Overflowing raises an error.
+    Checked,
+    /// Results wrap around at the bounds of the integer type.
+    Wrapping,
+    /// Results are clamped to the bounds of the integer type.
+    Saturating,
+}
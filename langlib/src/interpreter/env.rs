@@ -1,67 +1,134 @@
-use std::cell::RefCell;
+use std::{cell::RefCell, rc::Rc};
 
-use ahash::AHashMap;
+use ahash::{AHashMap, AHashSet};
 
-use crate::expr::Expr;
+use crate::{expr::Slot, value::Value};
 
 use super::err::RuntimeErr;
 
-#[derive(Debug, Eq, PartialEq, Clone, Default)]
+/// A local scope. Variables are stored in slots, which the resolver hands out in the order the variables are
+/// declared, so looking one up never has to hash its name.
+#[derive(Debug, Default)]
 pub struct Env {
-    vals: AHashMap<String, Expr>,
-    pub parent: Option<Box<RefCell<Env>>>,
+    slots: Vec<Value>,
+    pub parent: Option<Rc<RefCell<Env>>>,
 }
 
 impl Env {
-    /// Tries to get a variable from the environment.
-    pub fn get(&self, k: &str) -> Result<Expr, RuntimeErr> {
-        match self.vals.get(k) {
-            Some(v) => Ok(v.to_owned()),
+    /// Creates a new environment enclosed by `parent`. Variables of the parent stay shared with it, so any changes
+    /// made through the new environment are visible to everything else holding the parent.
+    pub fn new_enclosed(parent: Rc<RefCell<Env>>) -> Self {
+        Self {
+            slots: Vec::new(),
+            parent: Some(parent),
+        }
+    }
+
+    /// Defines a new variable and stores it in the given slot.
+    pub fn define(&mut self, slot: usize, v: Value) {
+        if slot >= self.slots.len() {
+            self.slots.resize(slot + 1, Value::Null);
+        }
+
+        self.slots[slot] = v;
+    }
+
+    /// Returns the environment `depth` levels above `env`.
+    pub fn ancestor(env: &Rc<RefCell<Env>>, depth: usize) -> Rc<RefCell<Env>> {
+        let mut env = Rc::clone(env);
 
-            // If there's a parent environment then attempt to get the variable from that.
-            None => match &self.parent {
-                Some(parent) => parent.borrow().get(k),
+        for _ in 0..depth {
+            let parent = match &env.borrow().parent {
+                Some(parent) => Rc::clone(parent),
+                None => break,
+            };
+
+            env = parent;
+        }
+
+        env
+    }
+
+    /// Gets the variable stored at `slot`, relative to `env`.
+    pub fn get_at(env: &Rc<RefCell<Env>>, slot: Slot, k: &str) -> Result<Value, RuntimeErr> {
+        env.borrow().get_slot(slot.depth, slot.index, k)
+    }
+
+    /// Walks up `depth` parents without cloning any of the pointers in between, and gets the variable at `index`.
+    fn get_slot(&self, depth: usize, index: usize, k: &str) -> Result<Value, RuntimeErr> {
+        match (depth, &self.parent) {
+            (0, _) => match self.slots.get(index) {
+                Some(v) => Ok(v.to_owned()),
                 None => Err(RuntimeErr::UndefinedVar(k.to_owned())),
             },
+            (depth, Some(parent)) => parent.borrow().get_slot(depth - 1, index, k),
+            (_, None) => Err(RuntimeErr::UndefinedVar(k.to_owned())),
         }
     }
 
-    /// Defines a new variable and stores it in the environment.
-    pub fn define(&mut self, k: String, v: Expr) {
-        self.vals.insert(k, v);
-    }
+    /// Assigns a value to the variable stored at `slot`, relative to `env`.
+    pub fn assign_at(
+        env: &Rc<RefCell<Env>>,
+        slot: Slot,
+        k: String,
+        v: Value,
+    ) -> Result<(), RuntimeErr> {
+        let ancestor = Env::ancestor(env, slot.depth);
+        let mut ancestor = ancestor.borrow_mut();
 
-    /// Assigns a value to a variable.
-    pub fn assign(&mut self, k: String, v: Expr) -> Result<(), RuntimeErr> {
-        match self.vals.get(&k) {
-            Some(_) => {
-                self.vals.insert(k, v);
+        match ancestor.slots.get_mut(slot.index) {
+            Some(val) => {
+                *val = v;
                 Ok(())
             }
-            None => match &self.parent {
-                Some(parent) => RefCell::borrow_mut(parent).assign(k, v),
-                None => todo!(),
-            },
+            None => Err(RuntimeErr::UndefinedVar(k)),
         }
     }
+}
+
+/// The global scope. Globals are still looked up by name, since functions are allowed to use globals that are
+/// declared after them.
+#[derive(Debug, Default)]
+pub struct Globals {
+    vals: AHashMap<String, Value>,
+    /// The globals declared with `const`. The resolver can't catch assignments to these from functions declared
+    /// before them, so they are checked here too.
+    constants: AHashSet<String>,
+}
 
-    /// Sets the parent of the environment.
-    pub fn set_parent(&mut self, parent: Env) {
-        self.parent = Some(Box::new(RefCell::new(parent)));
+impl Globals {
+    /// Tries to get a global variable.
+    pub fn get(&self, k: &str) -> Result<Value, RuntimeErr> {
+        match self.vals.get(k) {
+            Some(v) => Ok(v.to_owned()),
+            None => Err(RuntimeErr::UndefinedVar(k.to_owned())),
+        }
     }
 
-    /// Gets the parent of the environment.
-    pub fn get_parent(self) -> Option<Env> {
-        let p = match self.parent {
-            Some(p) => p,
-            None => return None,
-        };
+    /// Defines a new global variable.
+    pub fn define(&mut self, k: String, v: Value) {
+        self.constants.remove(&k);
+        self.vals.insert(k, v);
+    }
 
-        Some((*p).into_inner())
+    /// Defines a new global constant.
+    pub fn define_const(&mut self, k: String, v: Value) {
+        self.constants.insert(k.clone());
+        self.vals.insert(k, v);
     }
 
-    /// Deletes a variable from the current environment.
-    pub fn drop(&mut self, k: &str) {
-        self.vals.remove(k);
+    /// Assigns a value to a global variable.
+    pub fn assign(&mut self, k: String, v: Value) -> Result<(), RuntimeErr> {
+        if self.constants.contains(&k) {
+            return Err(RuntimeErr::ConstAssignment(k));
+        }
+
+        match self.vals.get_mut(&k) {
+            Some(val) => {
+                *val = v;
+                Ok(())
+            }
+            None => Err(RuntimeErr::UndefinedVar(k)),
+        }
     }
 }
@@ -1,24 +1,90 @@
-use crate::expr::Expr;
+use crate::{
+    bigint::BigInt,
+    lexer::{
+        op::{BinOp, UnOp},
+        span::{location, Span},
+    },
+    map::Key,
+    value::LexerThingType,
+};
 
 #[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
 pub enum RuntimeErr {
-    #[error("Variable \"{0}\" already exists.")]
-    VarRedefine(String),
     #[error("Variable \"{0}\" does not exist.")]
     UndefinedVar(String),
-    #[error("An invalid expression was found")]
-    InvalidExpr(Expr),
+    #[error("Cannot apply '{op}' to {lhs} and {rhs}{}.", location(.span))]
+    InvalidOperands {
+        op: BinOp,
+        lhs: LexerThingType,
+        rhs: LexerThingType,
+        span: Option<Span>,
+    },
+    #[error("Division by zero{}.", location(.0))]
+    DivisionByZero(Option<Span>),
+    #[error("Integer overflow in '{0}'{}.", location(.1))]
+    Overflow(BinOp, Option<Span>),
+    #[error("Cannot apply '{0}' to {1}{}.", location(.2))]
+    InvalidOperand(UnOp, LexerThingType, Option<Span>),
+    #[error("Index {0} is out of bounds for a list of length {1}.")]
+    IndexOutOfBounds(BigInt, usize),
+    #[error("The map has no key {0}.")]
+    KeyNotFound(Key),
+    #[error("Values of type {0} can't be used as map keys.")]
+    UnhashableKey(LexerThingType),
+    #[error("Undefined property \"{0}\".")]
+    UndefinedProperty(String),
+    #[error("Can't inherit from a value of type {0}, since it isn't a class.")]
+    SuperclassNotClass(LexerThingType),
+    #[error("Values of type {0} don't have properties.")]
+    NoProperties(LexerThingType),
+    #[error("Can't use the property \"{0}\" of null. Use \"?.\" to get a property only if the value isn't null.")]
+    NullProperty(String),
+    #[error("No pattern matched the value {0}.")]
+    NoMatch(String),
+    #[error("Can't assign to \"{0}\", since it's a constant.")]
+    ConstAssignment(String),
+    /// The path leads to the index, key or field that didn't match (e.g `[1]["name"]`), and is empty if the value
+    /// itself didn't.
+    #[error("Can't destructure the value {val} with the pattern {pattern}{}.", mismatch_path(.path))]
+    PatternMismatch {
+        val: String,
+        pattern: String,
+        path: String,
+    },
+    #[error("The pattern for \"{variant}\" has {found} fields, but the variant holds {expected}.")]
+    VariantFieldCount {
+        variant: String,
+        expected: usize,
+        found: usize,
+    },
+    #[error("Values of type {0} can't be looped over.")]
+    NotIterable(LexerThingType),
+    #[error("Values of type {0} can't be indexed.")]
+    NotIndexable(LexerThingType),
+    #[error("The expression can't be evaluated without running the code.")]
+    NotConstant,
     #[error("An unexpected type was found. Expected type '{0:?}'")]
     UnexpectedType(LexerThingType),
     #[error("An unexpected number of arguments was supplied. Expected {0} arguments, found {1}.")]
     BadArgLength(usize, usize),
+    #[error("Too few arguments were supplied. Expected at least {0} arguments, found {1}.")]
+    TooFewArgs(usize, usize),
+    #[error("Too many arguments were supplied. Expected at most {0} arguments, found {1}.")]
+    TooManyArgs(usize, usize),
+    #[error("The function has no parameter named \"{0}\".")]
+    UnknownNamedArg(String),
+    #[error("The argument \"{0}\" was supplied more than once.")]
+    DuplicateArg(String),
+    #[error("No value was supplied for the parameter \"{0}\".")]
+    MissingArg(String),
+    #[error("Stack overflow: calls were nested more than {0} deep.")]
+    StackOverflow(usize),
 }
-#[derive(Debug, Clone, Eq, PartialEq)]
-pub enum LexerThingType {
-    Int,
-    Str,
-    Bool,
-    Null,
-    Ident,
-    Func,
+
+/// Describes which part of a value didn't match a pattern, if it wasn't the whole value.
+fn mismatch_path(path: &str) -> String {
+    match path {
+        "" => String::new(),
+        path => format!(" (at {path})"),
+    }
 }
@@ -0,0 +1,15 @@
+use crate::value::Value;
+
+/// Describes how a statement finished executing. Jumping out of a loop or a function isn't an error, so this is
+/// kept apart from `Err`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Flow {
+    /// The statement ran to completion, so execution carries on with the next one.
+    Normal,
+    /// A `return` was reached, along with the value being returned.
+    Return(Value),
+    /// A `break` was reached.
+    Break,
+    /// A `continue` was reached.
+    Continue,
+}
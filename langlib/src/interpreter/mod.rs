@@ -1,27 +1,48 @@
+pub mod config;
 pub mod env;
 pub mod err;
+pub mod flow;
+mod pattern;
+mod tests;
 
+use colored::Colorize;
 use err::RuntimeErr;
 use std::{
     cell::RefCell,
     fs::OpenOptions,
     io::{self, Read},
+    mem,
     path::Path,
+    rc::Rc,
 };
 
 use crate::{
-    expr::{BinExpr, Expr},
-    lexer::{err::LexerError, Lexer},
+    class::{self, Class},
+    enums::Enum,
+    expr::{Expr, Slot, Var},
+    func::Closure,
+    lexer::{err::LexerError, op::BinOp, Lexer},
+    map::{Key, Map},
     parser::{err::ParserError, Parser},
+    resolver::{err::ResolverErr, Resolver},
     stmt::Stmt,
+    value::{LexerThingType, Range, Value},
 };
 
-use self::env::Env;
+use self::{
+    config::Config,
+    env::{Env, Globals},
+    flow::Flow,
+};
 
 #[derive(Debug)]
 pub struct Interpreter {
     instructions: Vec<Stmt>,
-    pub env: RefCell<Env>,
+    pub env: Rc<RefCell<Env>>,
+    pub globals: Globals,
+    config: Config,
+    /// How many calls are currently running.
+    call_depth: usize,
 }
 
 impl Interpreter {
@@ -32,50 +53,91 @@ impl Interpreter {
 
         file.read_to_string(&mut source)?;
 
-        let stmts = Parser::new(Lexer::new(&source).tokenize()?).get_statements()?;
+        let (tokens, spans) = Lexer::new(&source).tokenize_with_spans()?;
+
+        let stmts = Parser::with_spans(tokens, spans).get_statements()?;
 
         Ok(Self {
             instructions: stmts,
-            env: RefCell::new(Env::default()),
+            env: Rc::default(),
+            globals: Globals::default(),
+            config: Config::default(),
+            call_depth: 0,
         })
     }
 
     pub fn new(instructions: Vec<Stmt>) -> Self {
         Self {
             instructions,
-            env: RefCell::new(Env::default()),
+            env: Rc::default(),
+            globals: Globals::default(),
+            config: Config::default(),
+            call_depth: 0,
         }
     }
 
+    /// Replaces the default config of the interpreter.
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
     /// Interprets the code
     pub fn interpret(mut self) -> Result<(), Err> {
-        for i in 0..self.instructions.len() {
-            let stmt = self.instructions[i].clone();
-            self.execute_stmt(&stmt)?;
+        let mut instructions = mem::take(&mut self.instructions);
+
+        Resolver::new().resolve(&mut instructions)?;
+
+        for stmt in &instructions {
+            self.execute_stmt(stmt)?;
         }
 
         Ok(())
     }
 
-    /// Interprets the instructions
-    pub fn execute_stmt(&mut self, stmt: &Stmt) -> Result<(), Err> {
+    /// Interprets the instructions, and reports whether they jumped anywhere.
+    pub fn execute_stmt(&mut self, stmt: &Stmt) -> Result<Flow, Err> {
         match stmt {
             Stmt::Declaration(declaration) => {
-                let mut expr = self.visit_expr(&declaration.val)?;
+                let expr = self.visit_expr(&declaration.val)?;
+
+                match declaration.name.slot {
+                    None if declaration.constant => self
+                        .globals
+                        .define_const(declaration.name.ident.to_owned(), expr),
+                    _ => self.bind(&declaration.name, expr),
+                }
+            }
+
+            Stmt::Destructure(pattern, expr, constant) => {
+                let val = self.visit_expr(expr)?;
+                let mut path = Vec::new();
 
-                if let Expr::Func(func) = &mut expr {
-                    func.set_closure(self.env.clone().into_inner());
+                if !self.match_pattern_at(pattern, &val, &mut path)? {
+                    return Err(RuntimeErr::PatternMismatch {
+                        val: self.stringify(&val)?,
+                        pattern: pattern.to_string(),
+                        path: path.concat(),
+                    }
+                    .into());
                 }
 
-                self.env
-                    .borrow_mut()
-                    .define(declaration.ident.to_owned(), expr);
+                // Locals are kept constant by the resolver, but globals have to be marked
+                if *constant {
+                    for binding in pattern.bindings() {
+                        if binding.slot.is_none() {
+                            let v = self.globals.get(&binding.ident)?;
+
+                            self.globals.define_const(binding.ident.clone(), v);
+                        }
+                    }
+                }
             }
 
             Stmt::Print(exprr) => {
-                let result = self.visit_expr(exprr)?.eval()?;
+                let result = self.visit_expr(exprr)?;
 
-                println!("{result}");
+                println!("{}", self.stringify(&result)?);
             }
 
             Stmt::Expr(expr) => {
@@ -83,148 +145,521 @@ impl Interpreter {
             }
 
             Stmt::Block(stmts) => {
-                let prev = self.env.to_owned().into_inner();
-
-                let mut new_env = Env::default();
-                new_env.set_parent(prev);
-
-                self.env.replace(new_env);
-
-                let return_stmt =
-                    match stmts.iter().find_map(|stmt| match self.execute_stmt(stmt) {
-                        Ok(_) => None,
-                        Err(err) => match err {
-                            Err::ReturnStmt(expr) => Some(Ok(expr)),
-                            err => return Some(Err(err)),
-                        },
-                    }) {
-                        Some(expr) => expr?,
-                        None => Expr::Null,
-                    };
-
-                // Clean up
-                let parent = self
-                    .env
-                    .to_owned()
-                    .into_inner()
-                    .parent
-                    .unwrap()
-                    .into_inner();
-                self.env.replace(parent);
-
-                // Return the return statement if it's not null
-                if return_stmt != Expr::Null {
-                    self.execute_stmt(&Stmt::Return(return_stmt))?
-                }
+                let env = Env::new_enclosed(Rc::clone(&self.env));
+
+                return self.scoped(env, |interpreter| interpreter.execute_stmts(stmts));
             }
 
             Stmt::If(expr, block, else_block) => {
                 let result = match self.visit_expr(expr)? {
-                    Expr::Bool(b) => b,
-                    expr => return Err(Err::RuntimeErr(RuntimeErr::InvalidExpr(expr))),
+                    Value::Bool(b) => b,
+                    _ => {
+                        return Err(Err::RuntimeErr(RuntimeErr::UnexpectedType(
+                            LexerThingType::Bool,
+                        )))
+                    }
                 };
 
                 if result {
-                    self.execute_stmt(block)?;
+                    return self.execute_stmt(block);
                 } else if let Some(else_block) = else_block {
-                    self.execute_stmt(else_block)?;
+                    return self.execute_stmt(else_block);
                 }
             }
-            Stmt::While(condition, block) => {
+            Stmt::While(condition, block, increment) => {
                 while self.visit_expr(condition)?.try_into()? {
-                    self.execute_stmt(block)?;
+                    match self.execute_stmt(block)? {
+                        Flow::Break => break,
+                        Flow::Return(val) => return Ok(Flow::Return(val)),
+                        Flow::Normal | Flow::Continue => {}
+                    }
+
+                    if let Some(increment) = increment {
+                        self.execute_stmt(increment)?;
+                    }
                 }
             }
-            Stmt::Assignment(declaration) => {
-                let expr = self.visit_expr(&declaration.val)?;
+            Stmt::ForIn(for_in) => {
+                let iterable = self.visit_expr(&for_in.iterable)?;
+                let is_map = matches!(iterable, Value::Map(_));
+
+                for (key, item) in iterable.iter()? {
+                    // Every iteration gets a fresh scope, so closures made in the body each see their own variables
+                    let mut env = Env::new_enclosed(Rc::clone(&self.env));
+
+                    match for_in.idents.len() {
+                        // Looping over a map with a single variable gives its keys
+                        1 => env.define(0, if is_map { key } else { item }),
+                        _ => {
+                            env.define(0, key);
+                            env.define(1, item);
+                        }
+                    }
+
+                    match self.scoped(env, |interpreter| interpreter.execute_stmt(&for_in.body))? {
+                        Flow::Break => break,
+                        Flow::Return(val) => return Ok(Flow::Return(val)),
+                        Flow::Normal | Flow::Continue => {}
+                    }
+                }
+            }
+            Stmt::Assignment(var, expr) => {
+                let expr = self.visit_expr(expr)?;
+
+                match var.slot {
+                    Some(slot) => Env::assign_at(&self.env, slot, var.ident.to_owned(), expr)?,
+                    None => self.globals.assign(var.ident.to_owned(), expr)?,
+                }
+            }
+            Stmt::IndexAssignment(list, index, expr) => {
+                let list = self.visit_expr(list)?;
+                let index = self.visit_expr(index)?;
+                let expr = self.visit_expr(expr)?;
+
+                list.set_index(&index, expr)?;
+            }
+            Stmt::SetProperty(instance, ident, expr) => {
+                let instance = self.visit_expr(instance)?;
+                let expr = self.visit_expr(expr)?;
+
+                instance.set_property(ident, expr)?;
+            }
+            Stmt::Class(class) => {
+                let superclass = match &class.superclass {
+                    Some(superclass) => match self.get_var(superclass)? {
+                        Value::Class(superclass) => Some(superclass),
+                        v => return Err(RuntimeErr::SuperclassNotClass(v.type_of()).into()),
+                    },
+                    None => None,
+                };
+
+                // Methods capture the environment the class is declared in, just like functions do. Methods of
+                // subclasses get an extra scope holding `super`.
+                let env = match &superclass {
+                    Some(superclass) => {
+                        let mut env = Env::new_enclosed(Rc::clone(&self.env));
+                        env.define(0, Value::Class(Rc::clone(superclass)));
+
+                        Rc::new(RefCell::new(env))
+                    }
+                    None => Rc::clone(&self.env),
+                };
+
+                let methods = class
+                    .methods
+                    .iter()
+                    .map(|(ident, func)| {
+                        (ident.clone(), Closure::method(ident, func, Rc::clone(&env)))
+                    })
+                    .collect();
+
+                let class_val = Value::Class(Rc::new(Class::new(
+                    &class.name.ident,
+                    superclass,
+                    class.traits.clone(),
+                    methods,
+                )));
+
+                self.bind(&class.name, class_val);
+            }
+            Stmt::Match(parsed) => {
+                return self.eval_match(parsed, |interpreter, body| interpreter.execute_stmt(body))
+            }
+            Stmt::Enum(enum_decl) => {
+                let enum_val = Value::Enum(Rc::new(Enum::new(enum_decl)));
 
-                self.env
-                    .borrow_mut()
-                    .assign(declaration.ident.to_owned(), expr)?;
+                self.bind(&enum_decl.name, enum_val);
             }
-            Stmt::Return(expr) => {
-                return Err(Err::ReturnStmt(self.visit_expr(expr)?));
+            Stmt::Trait(_) => {}
+            Stmt::Return(expr, _) => return Ok(Flow::Return(self.visit_expr(expr)?)),
+            Stmt::Break(_) => return Ok(Flow::Break),
+            Stmt::Continue(_) => return Ok(Flow::Continue),
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    /// Executes statements in order, stopping early if one of them jumps.
+    pub fn execute_stmts(&mut self, stmts: &[Stmt]) -> Result<Flow, Err> {
+        for stmt in stmts {
+            match self.execute_stmt(stmt)? {
+                Flow::Normal => {}
+                flow => return Ok(flow),
             }
         }
 
-        Ok(())
+        Ok(Flow::Normal)
     }
 
     /// Visits an expression and executes it.
-    fn visit_expr(&mut self, expr: &Expr) -> Result<Expr, Err> {
+    pub fn visit_expr(&mut self, expr: &Expr) -> Result<Value, Err> {
         match expr {
-            Expr::Var(var) => match self.env.borrow().get(var) {
-                Ok(val) => Ok(val),
-                Err(err) => Err(Err::RuntimeErr(err)),
-            },
+            Expr::Var(var) => Ok(self.get_var(var)?),
             Expr::Bin(bin_expr) => {
                 let lhs = self.visit_expr(&bin_expr.lhs)?;
 
+                // The right side is only needed if the left side is null
+                if bin_expr.op == BinOp::Coalesce && lhs != Value::Null {
+                    return Ok(lhs);
+                }
+
                 let rhs = self.visit_expr(&bin_expr.rhs)?;
 
-                match Expr::eval(&Expr::Bin(BinExpr {
-                    lhs: Box::new(lhs),
-                    rhs: Box::new(rhs),
-                    op: bin_expr.op.clone(),
-                })) {
-                    Ok(result) => Ok(result),
-                    Err(err) => Err(Err::ParserError(err)),
+                if let Some(v) = self.call_operator(&bin_expr.op, &lhs, &rhs)? {
+                    return Ok(v);
                 }
+
+                Ok(bin_expr.apply(lhs, rhs, self.config.arithmetic)?)
             }
-            Expr::Unary(op, expr) => match Expr::eval(&Expr::Unary(op.clone(), expr.clone())) {
-                Ok(val) => Ok(val),
-                Err(err) => Err(Err::ParserError(err)),
-            },
+            Expr::Unary(op, expr, span) => {
+                let val = self.visit_expr(expr)?;
 
-            Expr::Funcall(callee, args) => {
-                let func = self.visit_expr(callee)?;
+                Ok(Expr::apply_unary(op, val, span)?)
+            }
 
-                let func = match func {
-                    Expr::Func(func) => func,
-                    _ => {
-                        return Err(Err::RuntimeErr(RuntimeErr::UnexpectedType(
-                            err::LexerThingType::Func,
-                        )))
-                    }
-                };
+            Expr::Funcall(callee, args, named) => {
+                let callee = self.visit_expr(callee)?;
+
+                self.call(callee, args, named)
+            }
+            Expr::OptionalGet(..) | Expr::OptionalCall(..) | Expr::Chain(_) => {
+                Ok(self.eval_chain(expr)?.unwrap_or(Value::Null))
+            }
+
+            // Functions capture the environment they are created in.
+            Expr::Func(func) => Ok(Value::Func(Closure::new(func, Rc::clone(&self.env)))),
 
-                let args: Vec<Expr> = args
+            Expr::List(items) => {
+                let items = items
                     .iter()
-                    .map(|expr| self.visit_expr(expr))
+                    .map(|item| self.visit_expr(item))
                     .try_collect()?;
 
-                if func.arg_len() != args.len() {
-                    return Err(Err::RuntimeErr(RuntimeErr::BadArgLength(
-                        func.arg_len(),
-                        args.len(),
-                    )));
+                Ok(Value::list(items))
+            }
+            Expr::Map(entries) => {
+                let mut map = Map::default();
+
+                for (key, v) in entries {
+                    let key = self.visit_expr(key)?;
+                    let v = self.visit_expr(v)?;
+
+                    map.insert(Key::try_from(&key)?, v);
+                }
+
+                Ok(Value::map(map))
+            }
+            Expr::Range(start, end, inclusive) => {
+                let start = self.visit_expr(start)?.try_into()?;
+                let end = self.visit_expr(end)?.try_into()?;
+
+                Ok(Value::Range(Range {
+                    start,
+                    end,
+                    inclusive: *inclusive,
+                }))
+            }
+            Expr::Index(list, index) => {
+                let list = self.visit_expr(list)?;
+                let index = self.visit_expr(index)?;
+
+                Ok(list.get_index(&index)?)
+            }
+            Expr::Ternary(condition, then_branch, else_branch) => {
+                match self.visit_expr(condition)? {
+                    Value::Bool(true) => self.visit_expr(then_branch),
+                    Value::Bool(false) => self.visit_expr(else_branch),
+                    _ => Err(RuntimeErr::UnexpectedType(LexerThingType::Bool).into()),
+                }
+            }
+            Expr::Match(parsed) => {
+                self.eval_match(parsed, |interpreter, body| interpreter.visit_expr(body))
+            }
+            Expr::Get(instance, ident) => Ok(self.visit_expr(instance)?.get_property(ident)?),
+            Expr::Super(var, ident) => {
+                let slot = var.slot.expect("super is always a local");
+
+                let superclass = match Env::get_at(&self.env, slot, &var.ident)? {
+                    Value::Class(superclass) => superclass,
+                    v => return Err(RuntimeErr::SuperclassNotClass(v.type_of()).into()),
+                };
+
+                // `this` is bound in the scope right below the one holding `super`
+                let this_slot = Slot {
+                    depth: slot.depth - 1,
+                    index: 0,
+                };
+                let this = Env::get_at(&self.env, this_slot, "this")?;
+
+                match superclass.find_method(ident) {
+                    Some(method) => Ok(Value::Func(method.bind(this))),
+                    None => Err(RuntimeErr::UndefinedProperty(ident.to_owned()).into()),
+                }
+            }
+            Expr::Slice(list, start, end) => {
+                let list = self.visit_expr(list)?;
+
+                let start = start
+                    .as_ref()
+                    .map(|start| self.visit_expr(start))
+                    .transpose()?;
+                let end = end.as_ref().map(|end| self.visit_expr(end)).transpose()?;
+
+                Ok(list.slice(start.as_ref(), end.as_ref())?)
+            }
+
+            Expr::Num(num) => Ok(Value::Int(*num)),
+            Expr::BigNum(num) => Ok(Value::BigInt(num.clone())),
+            Expr::Str(string) => Ok(Value::Str(string.clone())),
+            Expr::Bool(bool) => Ok(Value::Bool(*bool)),
+            Expr::Null => Ok(Value::Null),
+        }
+    }
+
+    /// Calls a function, class or variant constructor with the given arguments.
+    fn call(
+        &mut self,
+        callee: Value,
+        args: &[Expr],
+        named: &[(String, Expr)],
+    ) -> Result<Value, Err> {
+        if !matches!(
+            callee,
+            Value::Func(_) | Value::Class(_) | Value::Constructor(_)
+        ) {
+            return Err(Err::RuntimeErr(RuntimeErr::UnexpectedType(
+                LexerThingType::Func,
+            )));
+        }
+
+        let args: Vec<Value> = args
+            .iter()
+            .map(|expr| self.visit_expr(expr))
+            .try_collect()?;
+
+        let named: Vec<(String, Value)> = named
+            .iter()
+            .map(|(ident, expr)| Ok::<_, Err>((ident.clone(), self.visit_expr(expr)?)))
+            .try_collect()?;
+
+        if self.call_depth >= self.config.max_call_depth {
+            return Err(RuntimeErr::StackOverflow(self.config.max_call_depth).into());
+        }
+
+        self.call_depth += 1;
+
+        let result = match callee {
+            Value::Class(class) => Class::instantiate(&class, self, args, named),
+            Value::Func(func) => func.exec(self, args, named),
+            // The values of a variant can only be given in order
+            Value::Constructor(_) if !named.is_empty() => {
+                Err(RuntimeErr::UnknownNamedArg(named[0].0.clone()).into())
+            }
+            Value::Constructor(constructor) => Ok(constructor.construct(args)?),
+            _ => unreachable!("the callee was checked above"),
+        };
+
+        self.call_depth -= 1;
+
+        result
+    }
+
+    /// Evaluates one link of a chain with optional links. Returns `None` if an optional link found null, which
+    /// skips the rest of the chain.
+    fn eval_chain(&mut self, expr: &Expr) -> Result<Option<Value>, Err> {
+        let v = match expr {
+            Expr::Chain(chain) => self.eval_chain(chain)?,
+            Expr::OptionalGet(instance, ident) => match self.eval_chain(instance)? {
+                None | Some(Value::Null) => None,
+                Some(instance) => Some(instance.get_property(ident)?),
+            },
+            Expr::OptionalCall(callee, args, named) => match self.eval_chain(callee)? {
+                None | Some(Value::Null) => None,
+                Some(callee) => Some(self.call(callee, args, named)?),
+            },
+            Expr::Get(instance, ident) => match self.eval_chain(instance)? {
+                None => None,
+                Some(instance) => Some(instance.get_property(ident)?),
+            },
+            Expr::Funcall(callee, args, named) => match self.eval_chain(callee)? {
+                None => None,
+                Some(callee) => Some(self.call(callee, args, named)?),
+            },
+            Expr::Index(list, index) => match self.eval_chain(list)? {
+                None => None,
+                Some(list) => Some(list.get_index(&self.visit_expr(index)?)?),
+            },
+            expr => Some(self.visit_expr(expr)?),
+        };
+
+        Ok(v)
+    }
+
+    /// Calls the method overloading `op` if `lhs` is an instance that defines one. Otherwise, the reflected method
+    /// of `rhs` is called with `lhs` if `rhs` is an instance that defines one (see `reflected_operator_method`).
+    /// Returns `None` if the operator isn't overloaded, so the built-in behaviour should be used instead.
+    fn call_operator(
+        &mut self,
+        op: &BinOp,
+        lhs: &Value,
+        rhs: &Value,
+    ) -> Result<Option<Value>, Err> {
+        let find = |v: &Value, ident: Option<&str>| match (v, ident) {
+            (Value::Instance(instance), Some(ident)) => instance
+                .borrow()
+                .class
+                .find_method(ident)
+                .map(|method| method.bind(v.clone())),
+            _ => None,
+        };
+
+        let found = find(lhs, class::operator_method(op))
+            .map(|method| (method, rhs))
+            .or_else(|| {
+                find(rhs, class::reflected_operator_method(op)).map(|method| (method, lhs))
+            });
+
+        let (method, arg) = match found {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+
+        let result = method.exec(self, vec![arg.clone()], Vec::new())?;
+
+        match (op, result) {
+            (BinOp::NeqSign, Value::Bool(eq)) => Ok(Some(Value::Bool(!eq))),
+            (BinOp::EqSign, Value::Bool(eq)) => Ok(Some(Value::Bool(eq))),
+            (BinOp::EqSign | BinOp::NeqSign, _) => {
+                Err(RuntimeErr::UnexpectedType(LexerThingType::Bool).into())
+            }
+            (_, result) => Ok(Some(result)),
+        }
+    }
+
+    /// Converts a value into the string that `print` shows. Instances with a `__str__` method are converted by
+    /// calling it, including instances inside lists and maps.
+    pub fn stringify(&mut self, v: &Value) -> Result<String, Err> {
+        self.stringify_seen(v, &mut Vec::new())
+    }
+
+    /// Does the work of `stringify`. `seen` holds the lists and maps that are currently being converted, so a
+    /// collection that contains itself is shown as `[...]` or `{...}` instead of looping forever.
+    fn stringify_seen(&mut self, v: &Value, seen: &mut Vec<*const ()>) -> Result<String, Err> {
+        match v {
+            Value::Instance(instance) => {
+                let method = instance
+                    .borrow()
+                    .class
+                    .find_method("__str__")
+                    .map(|method| method.bind(v.clone()));
+
+                match method {
+                    Some(method) => match method.exec(self, Vec::new(), Vec::new())? {
+                        Value::Str(string) => Ok(string),
+                        _ => Err(RuntimeErr::UnexpectedType(LexerThingType::Str).into()),
+                    },
+                    None => Ok(v.to_string()),
+                }
+            }
+            Value::List(items) => {
+                let ptr = Rc::as_ptr(items) as *const ();
+
+                if seen.contains(&ptr) {
+                    return Ok("[...]".to_owned());
+                }
+
+                // The items are copied out first, since `__str__` is free to change the list
+                let items = items.borrow().clone();
+
+                seen.push(ptr);
+                let items: Result<Vec<String>, Err> = items
+                    .iter()
+                    .map(|item| self.stringify_seen(item, seen))
+                    .try_collect();
+                seen.pop();
+
+                Ok(format!("[{}]", items?.join(", ")))
+            }
+            Value::Map(map) => {
+                let ptr = Rc::as_ptr(map) as *const ();
+
+                if seen.contains(&ptr) {
+                    return Ok("{...}".to_owned());
                 }
 
-                func.exec(self, args)
+                let entries: Vec<_> = map
+                    .borrow()
+                    .iter()
+                    .map(|(k, v)| (Value::from(k.clone()), v.clone()))
+                    .collect();
+
+                seen.push(ptr);
+                let entries: Result<Vec<String>, Err> = entries
+                    .iter()
+                    .map(|(k, v)| Ok(format!("{k}: {}", self.stringify_seen(v, seen)?)))
+                    .try_collect();
+                seen.pop();
+
+                Ok(format!("{{{}}}", entries?.join(", ")))
+            }
+            Value::Variant(variant) if !variant.values.is_empty() => {
+                let values: Vec<String> = variant
+                    .values
+                    .iter()
+                    .map(|v| self.stringify_seen(v, seen))
+                    .try_collect()?;
+
+                Ok(format!(
+                    "{}({})",
+                    variant.decl().ident.cyan(),
+                    values.join(", ")
+                ))
             }
+            v => Ok(v.to_string()),
+        }
+    }
 
-            _ => Ok(expr.clone()),
+    /// Gets the value of a variable, from the slot the resolver found for it or from the globals.
+    fn get_var(&self, var: &Var) -> Result<Value, RuntimeErr> {
+        match var.slot {
+            Some(slot) => Env::get_at(&self.env, slot, &var.ident),
+            None => self.globals.get(&var.ident),
         }
     }
 
+    /// Runs `f` with `env` as the current environment, and restores the previous environment afterwards, even if
+    /// `f` fails.
+    pub fn scoped<T>(
+        &mut self,
+        env: Env,
+        f: impl FnOnce(&mut Self) -> Result<T, Err>,
+    ) -> Result<T, Err> {
+        let prev = mem::replace(&mut self.env, Rc::new(RefCell::new(env)));
+
+        let result = f(self);
+
+        self.env = prev;
+
+        result
+    }
+
     // Helper functions for other structs
-    pub fn define_var(&self, k: String, v: Expr) {
-        self.env.borrow_mut().define(k, v);
+    pub fn define_var(&self, slot: usize, v: Value) {
+        self.env.borrow_mut().define(slot, v);
     }
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum Err {
-    #[error("An error occurred during parsing.")]
+    #[error("Parser error: {0}")]
     ParserError(#[from] ParserError),
-    #[error("An error occurred during lexing.")]
+    #[error("Lexer error: {0}")]
     LexerError(#[from] LexerError),
-    #[error("A runtime error has occured.")]
+    #[error("Runtime error: {0}")]
     RuntimeErr(#[from] RuntimeErr),
+    #[error("Resolver error: {0}")]
+    ResolverErr(#[from] ResolverErr),
 
-    #[error("An IO error occured while attempting to read the file.")]
+    #[error("An IO error occured while attempting to read the file: {0}")]
     IOError(#[from] io::Error),
-
-    #[error("Not really an error.")]
-    ReturnStmt(Expr),
 }
@@ -0,0 +1,226 @@
+use std::rc::Rc;
+
+use crate::{
+    map::Key,
+    pattern::{Binding, Match, Pattern},
+    value::{LexerThingType, Value},
+};
+
+use super::{env::Env, err::RuntimeErr, Err, Interpreter};
+
+impl Interpreter {
+    /// Runs the body of the first arm of a `match` whose pattern matches the value, using `run` to run it. Every
+    /// arm gets a fresh scope for the variables its pattern binds.
+    pub(super) fn eval_match<B, T>(
+        &mut self,
+        parsed: &Match<B>,
+        run: impl Fn(&mut Self, &B) -> Result<T, Err>,
+    ) -> Result<T, Err> {
+        let val = self.visit_expr(&parsed.val)?;
+
+        for arm in &parsed.arms {
+            let env = Env::new_enclosed(Rc::clone(&self.env));
+
+            let result = self.scoped(env, |interpreter| {
+                if !interpreter.match_pattern(&arm.pattern, &val)? {
+                    return Ok(None);
+                }
+
+                if let Some(guard) = &arm.guard {
+                    if !interpreter.visit_expr(guard)?.try_into()? {
+                        return Ok(None);
+                    }
+                }
+
+                Ok(Some(run(interpreter, &arm.body)?))
+            })?;
+
+            if let Some(result) = result {
+                return Ok(result);
+            }
+        }
+
+        Err(RuntimeErr::NoMatch(self.stringify(&val)?).into())
+    }
+
+    /// Checks if a value matches a pattern, and binds the pattern's variables if it does. Variables may be bound
+    /// even if the pattern ends up not matching, so the caller should give every attempt its own scope.
+    pub fn match_pattern(&mut self, pattern: &Pattern, val: &Value) -> Result<bool, Err> {
+        self.match_pattern_at(pattern, val, &mut Vec::new())
+    }
+
+    /// Does the work of `match_pattern`. If the value doesn't match, `path` is left holding the indices, keys and
+    /// fields leading to the part that didn't match (e.g `[1]["name"]`).
+    pub(super) fn match_pattern_at(
+        &mut self,
+        pattern: &Pattern,
+        val: &Value,
+        path: &mut Vec<String>,
+    ) -> Result<bool, Err> {
+        match pattern {
+            Pattern::Wildcard => Ok(true),
+            Pattern::Literal(expr) => Ok(expr.eval()? == *val),
+            Pattern::Binding(binding) => {
+                self.bind(binding, val.clone());
+
+                Ok(true)
+            }
+            Pattern::List {
+                items: patterns,
+                rest,
+            } => {
+                let items = match val {
+                    // The items are copied out, since matching can run code that changes the list
+                    Value::List(items) => items.borrow().clone(),
+                    _ => return Ok(false),
+                };
+
+                // Items with defaults can be missing from the end of the list
+                let required = patterns
+                    .iter()
+                    .rposition(|pattern| !matches!(pattern, Pattern::Default(..)))
+                    .map_or(0, |i| i + 1);
+
+                if items.len() < required || (rest.is_none() && items.len() > patterns.len()) {
+                    return Ok(false);
+                }
+
+                for (i, pattern) in patterns.iter().enumerate() {
+                    path.push(format!("[{i}]"));
+
+                    let matched = match (pattern, items.get(i)) {
+                        (Pattern::Default(pattern, default), None) => {
+                            let v = self.visit_expr(default)?;
+
+                            self.match_pattern_at(pattern, &v, path)?
+                        }
+                        (pattern, Some(item)) => self.match_pattern_at(pattern, item, path)?,
+                        (_, None) => unreachable!("only items with defaults can be missing"),
+                    };
+
+                    if !matched {
+                        return Ok(false);
+                    }
+
+                    path.pop();
+                }
+
+                match rest {
+                    Some(rest) => {
+                        let rest_items = items.get(patterns.len()..).unwrap_or_default().to_vec();
+
+                        path.push(format!("[{}:]", patterns.len()));
+                        let matched =
+                            self.match_pattern_at(rest, &Value::list(rest_items), path)?;
+
+                        if matched {
+                            path.pop();
+                        }
+
+                        Ok(matched)
+                    }
+                    None => Ok(true),
+                }
+            }
+            // Instances are matched by their fields and methods, with string keys (e.g `let {name} = person;`)
+            Pattern::Map(entries) => {
+                if !matches!(val, Value::Map(_) | Value::Instance(_)) {
+                    return Ok(false);
+                }
+
+                for (key, pattern) in entries {
+                    let key = Key::try_from(&key.eval()?)?;
+
+                    let found = match (val, &key) {
+                        (Value::Map(map), key) => map.borrow().get(key).cloned(),
+                        (Value::Instance(instance), Key::Str(ident)) => {
+                            instance.borrow().get(ident, val).ok()
+                        }
+                        _ => None,
+                    };
+
+                    path.push(match (val, &key) {
+                        (Value::Instance(_), Key::Str(ident)) => format!(".{ident}"),
+                        _ => format!("[{key}]"),
+                    });
+
+                    let (pattern, v) = match (pattern, found) {
+                        (pattern, Some(v)) => (pattern, v),
+                        (Pattern::Default(pattern, default), None) => {
+                            (&**pattern, self.visit_expr(default)?)
+                        }
+                        (_, None) => return Ok(false),
+                    };
+
+                    if !self.match_pattern_at(pattern, &v, path)? {
+                        return Ok(false);
+                    }
+
+                    path.pop();
+                }
+
+                Ok(true)
+            }
+            Pattern::Variant {
+                enum_var,
+                variant,
+                fields,
+            } => {
+                let enum_val = match self.get_var(enum_var)? {
+                    Value::Enum(enum_val) => enum_val,
+                    _ => return Err(RuntimeErr::UnexpectedType(LexerThingType::Enum).into()),
+                };
+
+                // A misspelled variant would otherwise never match
+                if !enum_val.variants.iter().any(|decl| decl.ident == *variant) {
+                    return Err(RuntimeErr::UndefinedProperty(variant.clone()).into());
+                }
+
+                let val = match val {
+                    Value::Variant(val)
+                        if Rc::ptr_eq(&val.enum_val, &enum_val) && val.decl().ident == *variant =>
+                    {
+                        val
+                    }
+                    _ => return Ok(false),
+                };
+
+                let fields = match fields {
+                    Some(fields) => fields,
+                    None => return Ok(true),
+                };
+
+                if fields.len() != val.values.len() {
+                    return Err(RuntimeErr::VariantFieldCount {
+                        variant: format!("{}.{variant}", enum_val.name),
+                        expected: val.values.len(),
+                        found: fields.len(),
+                    }
+                    .into());
+                }
+
+                for ((pattern, v), field) in fields.iter().zip(&val.values).zip(&val.decl().fields)
+                {
+                    path.push(format!(".{field}"));
+
+                    if !self.match_pattern_at(pattern, v, path)? {
+                        return Ok(false);
+                    }
+
+                    path.pop();
+                }
+
+                Ok(true)
+            }
+            Pattern::Default(pattern, _) => self.match_pattern_at(pattern, val, path),
+        }
+    }
+
+    /// Defines a variable introduced by a declaration or a pattern.
+    pub(super) fn bind(&mut self, binding: &Binding, v: Value) {
+        match binding.slot {
+            Some(slot) => self.define_var(slot, v),
+            None => self.globals.define(binding.ident.clone(), v),
+        }
+    }
+}
@@ -0,0 +1,1481 @@
+#[cfg(test)]
+/// A module for interpreter unit tests.
+mod interpreter_tests {
+    use std::rc::Rc;
+
+    use colored::Colorize;
+
+    use crate::{
+        interpreter::{
+            config::{Arithmetic, Config},
+            err::RuntimeErr,
+            Err, Interpreter,
+        },
+        lexer::{
+            op::{BinOp, UnOp},
+            span::Span,
+            Lexer,
+        },
+        map::Key,
+        parser::{err::ParserError, Parser},
+        resolver::Resolver,
+        value::{LexerThingType, Value},
+    };
+
+    /// Runs the given source code, and returns the interpreter so its environment can be inspected.
+    fn run(source: &str) -> Result<Interpreter, Err> {
+        run_with(source, Config::default())
+    }
+
+    /// Runs the given source code with a custom config.
+    fn run_with(source: &str, config: Config) -> Result<Interpreter, Err> {
+        let (tokens, spans) = Lexer::new(source).tokenize_with_spans()?;
+
+        let mut stmts = Parser::with_spans(tokens, spans).get_statements()?;
+
+        Resolver::new().resolve(&mut stmts)?;
+
+        let mut interpreter = Interpreter::new(Vec::new()).with_config(config);
+
+        for stmt in &stmts {
+            interpreter.execute_stmt(stmt)?;
+        }
+
+        Ok(interpreter)
+    }
+
+    /// Gets the value of a global variable.
+    fn get(interpreter: &Interpreter, k: &str) -> Value {
+        interpreter.globals.get(k).unwrap()
+    }
+
+    #[test]
+    fn test_closure_keeps_state_between_calls() {
+        let interpreter = run("
+        func makeCounter() {
+            let i = 0;
+            func count() {
+                i = i + 1;
+                return i;
+            }
+            return count;
+        }
+
+        let count = makeCounter();
+        count();
+        count();
+        let a = count();
+
+        let other = makeCounter();
+        let b = other();
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "a"), Value::Int(3));
+        assert_eq!(get(&interpreter, "b"), Value::Int(1));
+    }
+
+    #[test]
+    fn test_anonymous_functions() {
+        let interpreter = run("
+        let add = func (a, b) { return a + b; };
+        let mul = (a, b) => a * b;
+        let twice = (f, x) => { return f(f(x)); };
+
+        let sum = add(2, 3);
+        let product = mul(4, 5);
+        let nested = twice((x) => x + 1, 1);
+        let immediate = (() => 7)();
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "sum"), Value::Int(5));
+        assert_eq!(get(&interpreter, "product"), Value::Int(20));
+        assert_eq!(get(&interpreter, "nested"), Value::Int(3));
+        assert_eq!(get(&interpreter, "immediate"), Value::Int(7));
+    }
+
+    #[test]
+    fn test_default_params() {
+        let interpreter = run("
+        let base = 1;
+        func f(a, b = base, c = a + b) { return [a, b, c]; }
+
+        let given = f(1, 2, 3);
+        let one = f(10);
+        base = 5;
+        let later = f(10);
+        let two = f(10, 20);
+        ")
+        .unwrap();
+
+        let list = |items: [i32; 3]| Value::list(items.into_iter().map(Value::Int).collect());
+
+        assert_eq!(get(&interpreter, "given"), list([1, 2, 3]));
+        assert_eq!(get(&interpreter, "one"), list([10, 1, 11]));
+        // Defaults are evaluated on every call, so they see the current value of `base`
+        assert_eq!(get(&interpreter, "later"), list([10, 5, 15]));
+        assert_eq!(get(&interpreter, "two"), list([10, 20, 30]));
+    }
+
+    #[test]
+    fn test_rest_params() {
+        let interpreter = run("
+        func f(a, b = 0, ...rest) { return rest; }
+
+        let none = f(1);
+        let empty = f(1, 2);
+        let extra = f(1, 2, 3, 4);
+        let all = ((...xs) => xs)(5, 6);
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "none"), Value::list(Vec::new()));
+        assert_eq!(get(&interpreter, "empty"), Value::list(Vec::new()));
+        assert_eq!(
+            get(&interpreter, "extra"),
+            Value::list(vec![Value::Int(3), Value::Int(4)])
+        );
+        assert_eq!(
+            get(&interpreter, "all"),
+            Value::list(vec![Value::Int(5), Value::Int(6)])
+        );
+    }
+
+    #[test]
+    fn test_arity_errors() {
+        let err = run("func f(a, b) {} f(1);").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::BadArgLength(2, 1))
+        ));
+
+        let err = run("func f(a, b = 1) {} f();").unwrap_err();
+        assert!(matches!(err, Err::RuntimeErr(RuntimeErr::TooFewArgs(1, 0))));
+
+        let err = run("func f(a, b = 1) {} f(1, 2, 3);").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::TooManyArgs(2, 3))
+        ));
+
+        // A rest parameter takes any number of extra arguments, but not fewer than required
+        let err = run("func f(a, ...rest) {} f();").unwrap_err();
+        assert!(matches!(err, Err::RuntimeErr(RuntimeErr::TooFewArgs(1, 0))));
+    }
+
+    #[test]
+    fn test_named_args() {
+        let interpreter = run("
+        func f(a, b, c = 3) { return [a, b, c]; }
+
+        let mixed = f(1, b: 2);
+        let reordered = f(c: 30, b: 20, a: 10);
+        let skipped = f(1, c: 5, b: 2);
+        ")
+        .unwrap();
+
+        let list = |items: [i32; 3]| Value::list(items.into_iter().map(Value::Int).collect());
+
+        assert_eq!(get(&interpreter, "mixed"), list([1, 2, 3]));
+        assert_eq!(get(&interpreter, "reordered"), list([10, 20, 30]));
+        assert_eq!(get(&interpreter, "skipped"), list([1, 2, 5]));
+    }
+
+    #[test]
+    fn test_named_arg_errors() {
+        let err = run("func f(a, b) {} f(1, d: 2);").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::UnknownNamedArg(ident)) if ident == "d"
+        ));
+
+        // Given positionally and by name
+        let err = run("func f(a, b) {} f(1, a: 2);").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::DuplicateArg(ident)) if ident == "a"
+        ));
+
+        let err = run("func f(a, b) {} f(b: 1, b: 2);").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::DuplicateArg(ident)) if ident == "b"
+        ));
+
+        let err = run("func f(a, b, c = 3) {} f(1, c: 2);").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::MissingArg(ident)) if ident == "b"
+        ));
+
+        let err = run("func f(a) {} f(1, 2, a: 3);").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::TooManyArgs(1, 2))
+        ));
+    }
+
+    #[test]
+    fn test_closure_mutates_enclosing_scope() {
+        let interpreter = run("
+        let x = 1;
+        let bump = () => { x = x + 10; };
+        bump();
+        bump();
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "x"), Value::Int(21));
+    }
+
+    #[test]
+    fn test_recursion() {
+        let interpreter = run("
+        func fib(n) {
+            if (n <= 1) return n;
+            return fib(n - 2) + fib(n - 1);
+        }
+
+        let result = fib(15);
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "result"), Value::Int(610));
+    }
+
+    #[test]
+    fn test_scope_is_restored_after_error() {
+        let mut interpreter = run("let x = 1;").unwrap();
+        let global_env = Rc::clone(&interpreter.env);
+
+        let mut stmts = Parser::new(Lexer::new("{ let y = 2; print z; }").tokenize().unwrap())
+            .get_statements()
+            .unwrap();
+
+        Resolver::new().resolve(&mut stmts).unwrap();
+
+        assert!(interpreter.execute_stmt(&stmts[0]).is_err());
+
+        assert_eq!(get(&interpreter, "x"), Value::Int(1));
+        assert!(Rc::ptr_eq(&global_env, &interpreter.env));
+    }
+
+    #[test]
+    fn test_function_values_share_their_code() {
+        let interpreter = run("
+        func f(a) { return a; }
+        let g = f;
+        ")
+        .unwrap();
+
+        match (get(&interpreter, "f"), get(&interpreter, "g")) {
+            (Value::Func(f), Value::Func(g)) => assert!(Rc::ptr_eq(&f.decl, &g.decl)),
+            _ => panic!("expected two functions"),
+        }
+    }
+
+    #[test]
+    fn test_unary_on_variable() {
+        let interpreter = run("
+        let t = true;
+        let f = !t;
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "f"), Value::Bool(false));
+    }
+
+    #[test]
+    fn test_break_and_continue() {
+        let interpreter = run("
+        let sum = 0;
+        for (let i = 0; i < 10; i = i + 1) {
+            if (i == 2) {
+                continue;
+            }
+            if (i == 5) {
+                break;
+            }
+            sum = sum + i;
+        }
+        ")
+        .unwrap();
+
+        // 0 + 1 + 3 + 4
+        assert_eq!(get(&interpreter, "sum"), Value::Int(8));
+    }
+
+    #[test]
+    fn test_return_from_nested_blocks() {
+        let interpreter = run("
+        let reached = false;
+        func f() {
+            {
+                {
+                    return;
+                }
+            }
+            reached = true;
+            return 1;
+        }
+        let result = f();
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "result"), Value::Null);
+        assert_eq!(get(&interpreter, "reached"), Value::Bool(false));
+    }
+
+    #[test]
+    fn test_invalid_operands() {
+        let err = run("let a = 1;\nlet b = \"a\" - a;").unwrap_err();
+
+        let err = match err {
+            Err::RuntimeErr(err) => err,
+            err => panic!("expected a runtime error, found {err:?}"),
+        };
+
+        assert_eq!(
+            err,
+            RuntimeErr::InvalidOperands {
+                op: BinOp::Sub,
+                lhs: LexerThingType::Str,
+                rhs: LexerThingType::Int,
+                span: Some(Span {
+                    start: 19,
+                    end: 26,
+                    line: 2,
+                    col: 9
+                })
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Cannot apply '-' to Str and Int at line 2, column 9."
+        );
+    }
+
+    #[test]
+    fn test_invalid_unary_operand() {
+        let err = run("let a = 1;\nlet b = !a;").unwrap_err();
+
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::InvalidOperand(
+                UnOp::Bang,
+                LexerThingType::Int,
+                Some(_)
+            ))
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Runtime error: Cannot apply '!' to Int at line 2, column 9."
+        );
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        let err = run("let a = 0; let b = 1 / a;").unwrap_err();
+
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::DivisionByZero(Some(_)))
+        ));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let source = "let a = 2147483647 + 1; let b = 0 - 2147483647 - 2;";
+
+        let checked = Config {
+            arithmetic: Arithmetic::Checked,
+            ..Config::default()
+        };
+        let err = run_with(source, checked).unwrap_err();
+
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::Overflow(BinOp::Add, Some(_)))
+        ));
+
+        let interpreter = run(source).unwrap();
+
+        assert_eq!(
+            get(&interpreter, "a"),
+            Value::BigInt("2147483648".parse().unwrap())
+        );
+        assert_eq!(
+            get(&interpreter, "b"),
+            Value::BigInt("-2147483649".parse().unwrap())
+        );
+
+        let wrapping = Config {
+            arithmetic: Arithmetic::Wrapping,
+            ..Config::default()
+        };
+        let interpreter = run_with(source, wrapping).unwrap();
+
+        assert_eq!(get(&interpreter, "a"), Value::Int(i32::MIN));
+        assert_eq!(get(&interpreter, "b"), Value::Int(i32::MAX));
+
+        let saturating = Config {
+            arithmetic: Arithmetic::Saturating,
+            ..Config::default()
+        };
+        let interpreter = run_with(source, saturating).unwrap();
+
+        assert_eq!(get(&interpreter, "a"), Value::Int(i32::MAX));
+        assert_eq!(get(&interpreter, "b"), Value::Int(i32::MIN));
+    }
+
+    #[test]
+    fn test_stack_overflow() {
+        let source = "
+        func forever(n) {
+            return forever(n + 1);
+        }
+
+        forever(0);
+        ";
+
+        let shallow = Config {
+            max_call_depth: 20,
+            ..Config::default()
+        };
+        let err = run_with(source, shallow).unwrap_err();
+
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::StackOverflow(20))
+        ));
+    }
+
+    #[test]
+    fn test_big_integers() {
+        let interpreter = run("
+        func factorial(n) {
+            if (n == 0) {
+                return 1;
+            }
+            return n * factorial(n - 1);
+        }
+
+        let big = factorial(25);
+        let back = big / factorial(24);
+        let literal = 100000000000000000000 - 99999999999999999999;
+        let bigger = big > 2147483647;
+        ")
+        .unwrap();
+
+        assert_eq!(
+            get(&interpreter, "big").to_string(),
+            "15511210043330985984000000".yellow().to_string()
+        );
+        // Results that fit are turned back into plain integers
+        assert_eq!(get(&interpreter, "back"), Value::Int(25));
+        assert_eq!(get(&interpreter, "literal"), Value::Int(1));
+        assert_eq!(get(&interpreter, "bigger"), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_lists_are_shared() {
+        let interpreter = run("
+        let xs = [1, 2, 3];
+        let ys = xs;
+        func set(list) {
+            list[-1] = 30;
+        }
+        set(ys);
+        xs[0] = 10;
+        let a = ys[0];
+        let b = xs[2];
+        let nested = [xs];
+        nested[0][1] = 20;
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "a"), Value::Int(10));
+        assert_eq!(get(&interpreter, "b"), Value::Int(30));
+        assert_eq!(
+            get(&interpreter, "xs"),
+            Value::list(vec![Value::Int(10), Value::Int(20), Value::Int(30)])
+        );
+    }
+
+    #[test]
+    fn test_collections_containing_themselves() {
+        let mut interpreter = run("
+        let xs = [\"a\"];
+        xs[0] = xs;
+        let ys = [\"a\"];
+        ys[0] = ys;
+        let m = {\"k\": \"v\"};
+        m[\"self\"] = m;
+        let same = xs == xs;
+        let twins = xs == ys;
+        let differ = xs == [xs, 1];
+        print xs;
+        print m;
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "same"), Value::Bool(true));
+        assert_eq!(get(&interpreter, "twins"), Value::Bool(true));
+        assert_eq!(get(&interpreter, "differ"), Value::Bool(false));
+
+        let xs = get(&interpreter, "xs");
+        assert_eq!(xs.to_string(), "[[...]]");
+        assert_eq!(interpreter.stringify(&xs).unwrap(), "[[...]]");
+
+        let m = get(&interpreter, "m");
+        assert_eq!(m.to_string(), "{k: v, self: {...}}");
+        assert_eq!(interpreter.stringify(&m).unwrap(), "{k: v, self: {...}}");
+    }
+
+    #[test]
+    fn test_list_slicing() {
+        let interpreter = run("
+        let xs = [1, 2, 3, 4];
+        let a = xs[1:3];
+        let b = xs[-2:];
+        let c = xs[:100];
+        let d = xs[3:1];
+        let copy = xs[:];
+        copy[0] = 5;
+        ")
+        .unwrap();
+
+        let list = |items: &[i32]| Value::list(items.iter().map(|&i| Value::Int(i)).collect());
+
+        assert_eq!(get(&interpreter, "a"), list(&[2, 3]));
+        assert_eq!(get(&interpreter, "b"), list(&[3, 4]));
+        assert_eq!(get(&interpreter, "c"), list(&[1, 2, 3, 4]));
+        assert_eq!(get(&interpreter, "d"), list(&[]));
+        // Slices are copies
+        assert_eq!(get(&interpreter, "xs"), list(&[1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_list_index_errors() {
+        let err = run("let xs = [1, 2]; let a = xs[-3];").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::IndexOutOfBounds(_, 2))
+        ));
+
+        let err = run("let xs = [1, 2]; xs[2] = 1;").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::IndexOutOfBounds(_, 2))
+        ));
+
+        let err = run("let xs = [1, 2]; let a = xs[true];").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::UnexpectedType(LexerThingType::Int))
+        ));
+
+        let err = run("let a = 1; let b = a[0];").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::NotIndexable(LexerThingType::Int))
+        ));
+    }
+
+    #[test]
+    fn test_maps() {
+        let interpreter = run("
+        let m = {\"b\": 1, \"a\": 2, 3: true};
+        let alias = m;
+        m[\"c\"] = 3;
+        alias[\"b\"] = 10;
+        let b = m[\"b\"];
+        let three = m[3];
+        let has = \"c\" in alias;
+        let missing = \"d\" in m;
+        let reordered = {\"a\": 1, \"b\": [2]} == {\"b\": [2], \"a\": 1};
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "b"), Value::Int(10));
+        assert_eq!(get(&interpreter, "reordered"), Value::Bool(true));
+        assert_eq!(get(&interpreter, "three"), Value::Bool(true));
+        assert_eq!(get(&interpreter, "has"), Value::Bool(true));
+        assert_eq!(get(&interpreter, "missing"), Value::Bool(false));
+
+        // Keys stay in the order they were first inserted
+        let keys: Vec<Key> = match get(&interpreter, "m") {
+            Value::Map(map) => map.borrow().iter().map(|(k, _)| k.clone()).collect(),
+            v => panic!("expected a map, found {v:?}"),
+        };
+
+        assert_eq!(
+            keys,
+            vec![
+                Key::Str("b".to_owned()),
+                Key::Str("a".to_owned()),
+                Key::Int(3),
+                Key::Str("c".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map_errors() {
+        let err = run("let m = {}; let a = m[\"a\"];").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::KeyNotFound(Key::Str(_)))
+        ));
+
+        let err = run("let m = {}; m[[1]] = 1;").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::UnhashableKey(LexerThingType::List))
+        ));
+
+        let err = run("let f = (x) => x; let m = {f: 1};").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::UnhashableKey(LexerThingType::Func))
+        ));
+    }
+
+    #[test]
+    fn test_for_in() {
+        let interpreter = run("
+        let sum = 0;
+        for (x in [1, 2, 3]) {
+            sum = sum + x;
+        }
+
+        let m = {\"a\": 1, \"b\": 2};
+        let keys = \"\";
+        for (k in m) {
+            keys = keys + k;
+        }
+        let total = 0;
+        for (k, v in m) {
+            total = total + v;
+        }
+
+        let reversed = \"\";
+        for (c in \"abc\") {
+            reversed = c + reversed;
+        }
+
+        let indices = 0;
+        for (i, x in [5, 5, 5]) {
+            indices = indices + i;
+        }
+
+        let count = 0;
+        for (n in 0..10) {
+            if (n == 2) {
+                continue;
+            }
+            if (n == 5) {
+                break;
+            }
+            count = count + n;
+        }
+
+        let inclusive = 0;
+        for (n in 1..=3) {
+            inclusive = inclusive + n;
+        }
+        let has = 3 in 1..=3;
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "sum"), Value::Int(6));
+        assert_eq!(get(&interpreter, "keys"), Value::Str("ab".to_owned()));
+        assert_eq!(get(&interpreter, "total"), Value::Int(3));
+        assert_eq!(get(&interpreter, "reversed"), Value::Str("cba".to_owned()));
+        assert_eq!(get(&interpreter, "indices"), Value::Int(3));
+        // 0 + 1 + 3 + 4
+        assert_eq!(get(&interpreter, "count"), Value::Int(8));
+        assert_eq!(get(&interpreter, "inclusive"), Value::Int(6));
+        assert_eq!(get(&interpreter, "has"), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_for_in_scopes() {
+        let interpreter = run("
+        let fs = [0, 0];
+        for (i in 0..2) {
+            fs[i] = () => i;
+        }
+        let a = fs[0]();
+        let b = fs[1]();
+
+        func find(xs, target) {
+            for (i, x in xs) {
+                if (x == target) {
+                    return i;
+                }
+            }
+            return -1;
+        }
+        let found = find([4, 5, 6], 6);
+        let missing = find([4, 5, 6], 7);
+        ")
+        .unwrap();
+
+        // Every iteration has its own variable
+        assert_eq!(get(&interpreter, "a"), Value::Int(0));
+        assert_eq!(get(&interpreter, "b"), Value::Int(1));
+        assert_eq!(get(&interpreter, "found"), Value::Int(2));
+        assert_eq!(get(&interpreter, "missing"), Value::Int(-1));
+    }
+
+    #[test]
+    fn test_for_in_errors() {
+        let err = run("for (x in 5) { print x; }").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::NotIterable(LexerThingType::Int))
+        ));
+
+        let err = run("for (a, b, c in [1]) { print a; }").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::ParserError(ParserError::InvalidLoopVars(_))
+        ));
+    }
+
+    #[test]
+    fn test_null() {
+        let interpreter = run("
+        class User {
+            init(name) { this.name = name; }
+            greet() { return \"hi \" + this.name; }
+        }
+        let user = User(\"ann\");
+        let nobody = null;
+
+        let names = [user?.name, nobody?.name, nobody?.name.first, nobody?.greet()];
+        let greeting = user?.greet();
+
+        let callback = null;
+        let skipped = callback?.(1);
+        let add = (x) => x + 1;
+        let called = add?.(1);
+
+        let calls = 0;
+        func bump() { calls = calls + 1; return calls; }
+        let a = nobody ?? \"default\";
+        let b = 0 ?? bump();
+        let c = nobody ?? null ?? 3;
+
+        let kind = match (nobody) { null => \"none\", _ => \"some\" };
+        ")
+        .unwrap();
+
+        assert_eq!(
+            get(&interpreter, "names"),
+            Value::list(vec![
+                Value::Str("ann".to_owned()),
+                Value::Null,
+                Value::Null,
+                Value::Null
+            ])
+        );
+        assert_eq!(
+            get(&interpreter, "greeting"),
+            Value::Str("hi ann".to_owned())
+        );
+        assert_eq!(get(&interpreter, "skipped"), Value::Null);
+        assert_eq!(get(&interpreter, "called"), Value::Int(2));
+        assert_eq!(get(&interpreter, "a"), Value::Str("default".to_owned()));
+        assert_eq!(get(&interpreter, "b"), Value::Int(0));
+        assert_eq!(get(&interpreter, "c"), Value::Int(3));
+        assert_eq!(get(&interpreter, "kind"), Value::Str("none".to_owned()));
+
+        // The right side of `??` only runs if it's needed
+        assert_eq!(get(&interpreter, "calls"), Value::Int(0));
+    }
+
+    #[test]
+    fn test_null_errors() {
+        let err = run("let a = null; let b = a.name;").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::NullProperty(ident)) if ident == "name"
+        ));
+
+        let err = run("let a = null; a.name = 1;").unwrap_err();
+        assert!(matches!(err, Err::RuntimeErr(RuntimeErr::NullProperty(_))));
+
+        // Only null short-circuits a chain
+        let err = run("let a = 1; let b = a?.name;").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::NoProperties(LexerThingType::Int))
+        ));
+    }
+
+    #[test]
+    fn test_ternary() {
+        let interpreter = run("
+        func sign(n) { return n > 0 ? 1 : n == 0 ? 0 : -1; }
+        let signs = [sign(5), sign(0), sign(-5)];
+
+        let calls = 0;
+        func bump() { calls = calls + 1; return calls; }
+        let a = true ? 1 : bump();
+        let b = false ? bump() : 2;
+        ")
+        .unwrap();
+
+        assert_eq!(
+            get(&interpreter, "signs"),
+            Value::list(vec![Value::Int(1), Value::Int(0), Value::Int(-1)])
+        );
+        assert_eq!(get(&interpreter, "a"), Value::Int(1));
+        assert_eq!(get(&interpreter, "b"), Value::Int(2));
+
+        // Only the branch that's picked runs
+        assert_eq!(get(&interpreter, "calls"), Value::Int(0));
+
+        let err = run("let a = 1 ? 2 : 3;").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::UnexpectedType(LexerThingType::Bool))
+        ));
+    }
+
+    #[test]
+    fn test_const() {
+        let interpreter =
+            run("const a = 1; func f() { const b = a + 1; return b; } let c = f();").unwrap();
+
+        assert_eq!(get(&interpreter, "a"), Value::Int(1));
+        assert_eq!(get(&interpreter, "c"), Value::Int(2));
+
+        // The resolver can't see that `f` assigns to a constant declared after it
+        let err = run("func f() { a = 2; } const a = 1; f();").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::ConstAssignment(ident)) if ident == "a"
+        ));
+    }
+
+    #[test]
+    fn test_const_destructure() {
+        let interpreter = run("
+        const [a, b] = [1, 2];
+        const {x, y = 5} = {\"x\": 3};
+        func f() {
+            const [c, ...rest] = [a, b];
+            return c + rest[0];
+        }
+        let sum = f();
+        ")
+        .unwrap();
+
+        for (name, v) in [("a", 1), ("b", 2), ("x", 3), ("y", 5), ("sum", 3)] {
+            assert_eq!(get(&interpreter, name), Value::Int(v));
+        }
+
+        let err = run("func f() { b = 3; } const [a, b] = [1, 2]; f();").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::ConstAssignment(ident)) if ident == "b"
+        ));
+    }
+
+    #[test]
+    fn test_classes() {
+        let interpreter = run("
+        class Counter {
+            init(start) {
+                this.count = start;
+            }
+
+            inc() {
+                this.count = this.count + 1;
+                return this;
+            }
+        }
+
+        let c = Counter(5);
+        c.inc().inc();
+        let count = c.count;
+
+        let inc = c.inc;
+        inc();
+        let bound = c.count;
+
+        let other = Counter(0);
+        other.label = \"other\";
+        let label = other.label;
+        let same = c == c;
+        let different = c == other;
+
+        class Early {
+            init(stop) {
+                this.stopped = stop;
+                if (stop) {
+                    return;
+                }
+                this.ran = true;
+            }
+        }
+
+        let early = Early(false);
+        let init_returns = [early.init(true) == early, early.init(false) == early, Counter(1).init(2).count];
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "count"), Value::Int(7));
+        // Methods stay bound to their instance after being taken off it
+        assert_eq!(get(&interpreter, "bound"), Value::Int(8));
+        assert_eq!(get(&interpreter, "label"), Value::Str("other".to_owned()));
+        assert_eq!(get(&interpreter, "same"), Value::Bool(true));
+        assert_eq!(get(&interpreter, "different"), Value::Bool(false));
+        // Calling `init` directly returns the instance, both after a bare `return` and at the end of the body
+        assert_eq!(
+            get(&interpreter, "init_returns"),
+            Value::list(vec![Value::Bool(true), Value::Bool(true), Value::Int(2)])
+        );
+    }
+
+    #[test]
+    fn test_class_errors() {
+        let err = run("class A {} let a = A(); print a.x;").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::UndefinedProperty(ident)) if ident == "x"
+        ));
+
+        let err = run("let n = 1; n.x = 2;").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::NoProperties(LexerThingType::Int))
+        ));
+
+        let err = run("class A {} A(1);").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::BadArgLength(0, 1))
+        ));
+    }
+
+    #[test]
+    fn test_inheritance() {
+        let interpreter = run("
+        class A {
+            init(x) {
+                this.x = x;
+            }
+
+            name() {
+                return \"A\";
+            }
+
+            describe() {
+                return this.name();
+            }
+        }
+
+        class B < A {
+            init(x, y) {
+                super.init(x);
+                this.y = y;
+            }
+
+            name() {
+                return \"B\" + super.name();
+            }
+        }
+
+        class C < B {
+            name() {
+                let f = () => super.name();
+                return \"C\" + f();
+            }
+        }
+
+        let c = C(1, 2);
+        let x = c.x;
+        let y = c.y;
+        let described = c.describe();
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "x"), Value::Int(1));
+        assert_eq!(get(&interpreter, "y"), Value::Int(2));
+        assert_eq!(get(&interpreter, "described"), Value::Str("CBA".to_owned()));
+    }
+
+    #[test]
+    fn test_inheritance_errors() {
+        let err = run("func f() { return 1; } let g = f; class A < g {} A();").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::SuperclassNotClass(LexerThingType::Func))
+        ));
+
+        let err = run("class A {} class B < A { f() { return super.g(); } } B().f();").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::UndefinedProperty(ident)) if ident == "g"
+        ));
+    }
+    #[test]
+    fn test_traits() {
+        let interpreter = run("
+        trait Plugin {
+            run(input);
+        }
+
+        class Base impl Plugin {
+            run(input) {
+                return input;
+            }
+        }
+
+        class Upper < Base {}
+        class Other {}
+        let plugin = Upper();
+        ")
+        .unwrap();
+
+        let implements = |k| match get(&interpreter, k) {
+            Value::Class(class) => class.implements("Plugin"),
+            _ => panic!(),
+        };
+
+        // Implementations are inherited
+        assert!(implements("Base"));
+        assert!(implements("Upper"));
+        assert!(!implements("Other"));
+    }
+
+    #[test]
+    fn test_operator_overloading() {
+        let mut interpreter = run("
+        class Money {
+            init(cents) {
+                this.cents = cents;
+            }
+
+            __add__(other) {
+                return Money(this.cents + other.cents);
+            }
+
+            __eq__(other) {
+                return this.cents == other.cents;
+            }
+
+            __lt__(other) {
+                return this.cents < other.cents;
+            }
+
+            __str__() {
+                return \"money\";
+            }
+        }
+
+        let total = (Money(150) + Money(250)).cents;
+        let eq = Money(1) == Money(1);
+        let neq = Money(1) != Money(1);
+        let lt = Money(1) < Money(2);
+        let wallet = [Money(1)];
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "total"), Value::Int(400));
+        assert_eq!(get(&interpreter, "eq"), Value::Bool(true));
+        assert_eq!(get(&interpreter, "neq"), Value::Bool(false));
+        assert_eq!(get(&interpreter, "lt"), Value::Bool(true));
+
+        let wallet = get(&interpreter, "wallet");
+        assert_eq!(interpreter.stringify(&wallet).unwrap(), "[money]");
+    }
+
+    #[test]
+    fn test_operator_overloading_errors() {
+        // Operators that aren't overloaded keep their built-in behaviour
+        let err = run("class A {} let a = A() + 1;").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::InvalidOperands { op: BinOp::Add, .. })
+        ));
+
+        let err = run("class A { __eq__(o) { return 1; } } let a = A() == A();").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::UnexpectedType(LexerThingType::Bool))
+        ));
+
+        // `>` isn't worked out from `__lt__`, so an instance on the left needs a `__gt__` method
+        let err = run("class A { __lt__(o) { return true; } } let a = A() > 1;").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::InvalidOperands {
+                op: BinOp::GreaterSign,
+                ..
+            })
+        ));
+
+        // Arithmetic with an instance on the right needs a reflected method
+        let err = run("class A { __add__(o) { return 1; } } let a = 1 + A();").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::InvalidOperands { op: BinOp::Add, .. })
+        ));
+    }
+
+    #[test]
+    fn test_reflected_operators() {
+        let interpreter = run("
+        class Money {
+            init(cents) {
+                this.cents = cents;
+            }
+
+            __radd__(other) {
+                return Money(other + this.cents);
+            }
+
+            __rsub__(other) {
+                return Money(other - this.cents);
+            }
+
+            __eq__(other) {
+                return this.cents == other;
+            }
+
+            __lt__(other) {
+                return this.cents < other;
+            }
+
+            __gt__(other) {
+                return this.cents > other;
+            }
+        }
+
+        let total = (100 + Money(50)).cents;
+        let change = (100 - Money(30)).cents;
+        let eq = 5 == Money(5);
+        let neq = 5 != Money(5);
+        let gt = 10 > Money(5);
+        let lt = 1 < Money(5);
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "total"), Value::Int(150));
+        assert_eq!(get(&interpreter, "change"), Value::Int(70));
+        assert_eq!(get(&interpreter, "eq"), Value::Bool(true));
+        assert_eq!(get(&interpreter, "neq"), Value::Bool(false));
+        assert_eq!(get(&interpreter, "gt"), Value::Bool(true));
+        assert_eq!(get(&interpreter, "lt"), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_enums() {
+        let mut interpreter = run("
+        enum Shape { Circle(r), Rect(w, h), Empty }
+
+        let circle = Shape.Circle(2);
+        let r = circle.r;
+        let same = circle == Shape.Circle(2);
+        let different = circle == Shape.Circle(3);
+        let empty = Shape.Empty == Shape.Empty;
+
+        enum Other { Empty }
+        let mixed = Shape.Empty == Other.Empty;
+
+        let rect = Shape.Rect;
+        let shapes = [rect(1, 2), Shape.Empty];
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "r"), Value::Int(2));
+        assert_eq!(get(&interpreter, "same"), Value::Bool(true));
+        assert_eq!(get(&interpreter, "different"), Value::Bool(false));
+        assert_eq!(get(&interpreter, "empty"), Value::Bool(true));
+        // Variants of different enums are never equal, even if they have the same name
+        assert_eq!(get(&interpreter, "mixed"), Value::Bool(false));
+
+        let shapes = get(&interpreter, "shapes");
+        assert_eq!(
+            interpreter.stringify(&shapes).unwrap(),
+            format!(
+                "[{}({}, {}), {}]",
+                "Rect".cyan(),
+                "1".yellow(),
+                "2".yellow(),
+                "Empty".cyan()
+            )
+        );
+    }
+
+    #[test]
+    fn test_enum_errors() {
+        let err = run("enum E { A(x) } let a = E.A(1, 2);").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::BadArgLength(1, 2))
+        ));
+
+        let err = run("enum E { A(x) } let a = E.B;").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::UndefinedProperty(ident)) if ident == "B"
+        ));
+
+        let err = run("enum E { A(x) } let a = E.A(x: 1);").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::UnknownNamedArg(ident)) if ident == "x"
+        ));
+    }
+
+    #[test]
+    fn test_match() {
+        let interpreter = run("
+        enum Shape { Circle(r), Rect(w, h), Empty }
+
+        func area(shape) {
+            return match (shape) {
+                Shape.Circle(r) => 3 * r * r,
+                Shape.Rect(w, h) if w == h => w * w,
+                Shape.Rect(w, h) => w * h,
+                Shape.Empty => 0,
+            };
+        }
+
+        let areas = [area(Shape.Circle(2)), area(Shape.Rect(3, 3)), area(Shape.Rect(2, 5)), area(Shape.Empty)];
+
+        func describe(v) {
+            return match (v) {
+                0 => \"zero\",
+                -1 => \"minus one\",
+                \"hi\" => \"greeting\",
+                [] => \"empty\",
+                [_, [x, ...]] => x,
+                [first, ...rest] => rest,
+                {\"name\": name} => name,
+                _ => \"other\",
+            };
+        }
+
+        let described = [
+            describe(0), describe(-1), describe(\"hi\"), describe([]), describe([1, [\"nested\", 2]]),
+            describe([1, 2, 3]), describe({\"name\": \"bob\", \"age\": 3}), describe(true)
+        ];
+        ")
+        .unwrap();
+
+        assert_eq!(
+            get(&interpreter, "areas"),
+            Value::list(vec![
+                Value::Int(12),
+                Value::Int(9),
+                Value::Int(10),
+                Value::Int(0)
+            ])
+        );
+
+        let str = |s: &str| Value::Str(s.to_owned());
+        assert_eq!(
+            get(&interpreter, "described"),
+            Value::list(vec![
+                str("zero"),
+                str("minus one"),
+                str("greeting"),
+                str("empty"),
+                str("nested"),
+                Value::list(vec![Value::Int(2), Value::Int(3)]),
+                str("bob"),
+                str("other")
+            ])
+        );
+    }
+
+    #[test]
+    fn test_match_stmt() {
+        let interpreter = run("
+        let found = -1;
+        let i = 0;
+        for (x in [5, 7, 9]) {
+            match (x) {
+                7 => {
+                    found = i;
+                    break;
+                }
+                _ => {}
+            }
+            i = i + 1;
+        }
+
+        func sign(n) {
+            match (n) {
+                0 => { return 0; }
+                n if n < 0 => { return -1; }
+                _ => {}
+            }
+            return 1;
+        }
+        let signs = [sign(-5), sign(0), sign(5)];
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "found"), Value::Int(1));
+        assert_eq!(
+            get(&interpreter, "signs"),
+            Value::list(vec![Value::Int(-1), Value::Int(0), Value::Int(1)])
+        );
+    }
+
+    #[test]
+    fn test_destructure() {
+        let interpreter = run("
+        let [a, [b, c], ...rest] = [1, [2, 3], 4, 5];
+        let {name, age, city = \"Oslo\"} = {\"name\": \"Ann\", \"age\": 30};
+        let [x, y = 10, z = x + y] = [1];
+        let {\"pos\": [px, py]} = {\"pos\": [3, 4]};
+
+        func tail(xs) {
+            let [_, ...t] = xs;
+            return t;
+        }
+        let t = tail([1, 2, 3]);
+        ")
+        .unwrap();
+
+        for (name, v) in [
+            ("a", 1),
+            ("b", 2),
+            ("c", 3),
+            ("age", 30),
+            ("x", 1),
+            ("y", 10),
+            ("z", 11),
+            ("px", 3),
+            ("py", 4),
+        ] {
+            assert_eq!(get(&interpreter, name), Value::Int(v));
+        }
+
+        assert_eq!(
+            get(&interpreter, "rest"),
+            Value::list(vec![Value::Int(4), Value::Int(5)])
+        );
+        assert_eq!(get(&interpreter, "name"), Value::Str("Ann".to_owned()));
+        assert_eq!(get(&interpreter, "city"), Value::Str("Oslo".to_owned()));
+        assert_eq!(
+            get(&interpreter, "t"),
+            Value::list(vec![Value::Int(2), Value::Int(3)])
+        );
+    }
+
+    #[test]
+    fn test_destructure_errors() {
+        let err = run("let [a, b] = [1, 2, 3];").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::PatternMismatch { pattern, .. }) if pattern == "[a, b]"
+        ));
+
+        let err = run("let [a, [b, ...]] = [1, 2];").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::PatternMismatch { pattern, .. }) if pattern == "[a, [b, ...]]"
+        ));
+
+        let err = run("let {name} = {\"age\": 1};").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::PatternMismatch { pattern, .. }) if pattern == "{\"name\": name}"
+        ));
+
+        let err = run("let [a] = 1;").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::PatternMismatch { path, .. }) if path.is_empty()
+        ));
+
+        // The error points at the part of the value that didn't match
+        let err = run("let [a, {\"pos\": [x, y]}] = [1, {\"pos\": [2]}];").unwrap_err();
+        assert!(matches!(
+            &err,
+            Err::RuntimeErr(RuntimeErr::PatternMismatch { path, .. }) if path == "[1][\"pos\"]"
+        ));
+        assert!(err.to_string().ends_with("(at [1][\"pos\"])."));
+
+        let err = run("let {name} = {\"age\": 1};").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::PatternMismatch { path, .. }) if path == "[\"name\"]"
+        ));
+
+        let err = run("class P {} let {name} = P();").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::PatternMismatch { path, .. }) if path == ".name"
+        ));
+    }
+
+    #[test]
+    fn test_destructure_instances() {
+        let interpreter = run("
+        class Person {
+            init(name, pos) {
+                this.name = name;
+                this.pos = pos;
+            }
+
+            greet() {
+                return \"hi \" + this.name;
+            }
+        }
+
+        let {name, \"pos\": [x, y], age = 30, greet} = Person(\"Ann\", [1, 2]);
+        let greeting = greet();
+        ")
+        .unwrap();
+
+        assert_eq!(get(&interpreter, "name"), Value::Str("Ann".to_owned()));
+        assert_eq!(get(&interpreter, "x"), Value::Int(1));
+        assert_eq!(get(&interpreter, "y"), Value::Int(2));
+        assert_eq!(get(&interpreter, "age"), Value::Int(30));
+        assert_eq!(
+            get(&interpreter, "greeting"),
+            Value::Str("hi Ann".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_match_errors() {
+        let err = run("let a = match (3) { 1 => 1, 2 => 2 };").unwrap_err();
+        assert!(matches!(err, Err::RuntimeErr(RuntimeErr::NoMatch(_))));
+
+        let err = run("enum E { A(x) } let a = match (E.A(1)) { E.C => 1 };").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::UndefinedProperty(ident)) if ident == "C"
+        ));
+
+        let err = run("
+        enum E { A(x, y) }
+        func first(Enum, v) { return match (v) { Enum.A(x) => x }; }
+        first(E, E.A(1, 2));
+        ")
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::VariantFieldCount { variant, expected: 2, found: 1 }) if variant == "E.A"
+        ));
+
+        let err = run("let a = match (1) { x if [x] => 1 };").unwrap_err();
+        assert!(matches!(
+            err,
+            Err::RuntimeErr(RuntimeErr::UnexpectedType(LexerThingType::Bool))
+        ));
+    }
+}
@@ -1,11 +1,17 @@
 pub mod err;
 pub mod op;
+pub mod span;
 pub mod tests;
 pub mod token;
 
+use std::num::IntErrorKind;
+
+use crate::bigint::BigInt;
+
 use self::{
     err::LexerError,
     op::{BinOp, UnOp},
+    span::Span,
     token::{Keyword, Token},
 };
 
@@ -17,17 +23,47 @@ pub struct Lexer<'a> {
 
 impl<'a> Lexer<'a> {
     /// Creates a new `Lexer` instance
-    pub fn new(input: &'a str) -> Lexer {
+    pub fn new(input: &'a str) -> Lexer<'a> {
         Self { input, position: 0 }
     }
 
     /// Tokenizes a string
     pub fn tokenize(&mut self) -> Result<Vec<Token>, LexerError> {
-        let mut vec = Vec::new();
+        Ok(self.tokenize_with_spans()?.0)
+    }
+
+    /// Tokenizes a string, and also returns where each token was found in the source.
+    pub fn tokenize_with_spans(&mut self) -> Result<(Vec<Token>, Vec<Span>), LexerError> {
+        let mut tokens = Vec::new();
+        let mut spans = Vec::new();
+
+        // The line and column are worked out as we go, so the source is only scanned once
+        let (mut line, mut col, mut scanned) = (1, 1, 0);
 
         loop {
             match self.next_token() {
-                Ok(token) => vec.push(token.0),
+                Ok((token, len)) => {
+                    let start = self.position - len;
+
+                    for c in self.input[scanned..start].chars() {
+                        if c == '\n' {
+                            line += 1;
+                            col = 1;
+                        } else {
+                            col += 1;
+                        }
+                    }
+
+                    scanned = start;
+
+                    tokens.push(token);
+                    spans.push(Span {
+                        start,
+                        end: self.position,
+                        line,
+                        col,
+                    });
+                }
                 Err(err) => match err {
                     LexerError::UnexpectedEOF => break,
                     _ => return Err(err),
@@ -35,7 +71,7 @@ impl<'a> Lexer<'a> {
             }
         }
 
-        Ok(vec)
+        Ok((tokens, spans))
     }
 
     /// Returns the next token with its length
@@ -68,6 +104,8 @@ impl<'a> Lexer<'a> {
             '=' => {
                 if Some('=') == data.chars().nth(1) {
                     Ok((Token::Op(BinOp::EqSign), 2))
+                } else if Some('>') == data.chars().nth(1) {
+                    Ok((Token::Arrow, 2))
                 } else {
                     Ok((Token::DeclarationSign, 1))
                 }
@@ -77,8 +115,31 @@ impl<'a> Lexer<'a> {
             ')' => Ok((Token::RightBracket, 1)),
             '{' => Ok((Token::LeftCurly, 1)),
             '}' => Ok((Token::RightCurly, 1)),
+            '[' => Ok((Token::LeftSquare, 1)),
+            ']' => Ok((Token::RightSquare, 1)),
             ';' => Ok((Token::Semi, 1)),
+            ':' => Ok((Token::Colon, 1)),
             ',' => Ok((Token::Comma, 1)),
+            '?' => {
+                if data.starts_with("??") {
+                    Ok((Token::Op(BinOp::Coalesce), 2))
+                } else if data.starts_with("?.") {
+                    Ok((Token::QuestionDot, 2))
+                } else {
+                    Ok((Token::Question, 1))
+                }
+            }
+            '.' => {
+                if data.starts_with("...") {
+                    Ok((Token::Ellipsis, 3))
+                } else if data.starts_with("..=") {
+                    Ok((Token::DotDotEq, 3))
+                } else if data.starts_with("..") {
+                    Ok((Token::DotDot, 2))
+                } else {
+                    Ok((Token::Dot, 1))
+                }
+            }
             '!' => {
                 if Some('=') == data.chars().nth(1) {
                     Ok((Token::Op(BinOp::NeqSign), 2))
@@ -139,11 +200,21 @@ impl<'a> Lexer<'a> {
             _ => {}
         };
 
-        // take until we encounter a whitespace
-        let (word, len) = Lexer::take_while(data, |c| c.is_alphanumeric())?;
+        // take until we encounter a whitespace. Underscores are allowed too, so special methods like `__add__`
+        // can be named
+        let (word, len) = Lexer::take_while(data, |c| c.is_alphanumeric() || c == '_')?;
+
+        // Nothing could be taken, so the character doesn't start any token
+        if len == 0 {
+            return Err(LexerError::InvalidChar(
+                data.chars().next().expect("data is not empty"),
+            ));
+        }
 
         let word = match word.as_str() {
             "let" => Token::Keyword(Keyword::Let),
+            "const" => Token::Keyword(Keyword::Const),
+            "null" => Token::Keyword(Keyword::Null),
             "true" => Token::Keyword(Keyword::True),
             "false" => Token::Keyword(Keyword::False),
             "print" => Token::Keyword(Keyword::Print),
@@ -153,8 +224,18 @@ impl<'a> Lexer<'a> {
             "for" => Token::Keyword(Keyword::For),
             "func" => Token::Keyword(Keyword::Func),
             "return" => Token::Keyword(Keyword::Return),
+            "break" => Token::Keyword(Keyword::Break),
+            "continue" => Token::Keyword(Keyword::Continue),
+            "class" => Token::Keyword(Keyword::Class),
+            "this" => Token::Keyword(Keyword::This),
+            "super" => Token::Keyword(Keyword::Super),
+            "trait" => Token::Keyword(Keyword::Trait),
+            "impl" => Token::Keyword(Keyword::Impl),
+            "enum" => Token::Keyword(Keyword::Enum),
+            "match" => Token::Keyword(Keyword::Match),
             "and" => Token::Op(BinOp::And),
             "or" => Token::Op(BinOp::Or),
+            "in" => Token::Op(BinOp::In),
             s => Token::Ident(s.to_owned()),
         };
 
@@ -170,6 +251,13 @@ impl<'a> Lexer<'a> {
             // If it's ok we need to try to parse the number
             Ok((num_string, len)) => match num_string.parse::<i32>() {
                 Ok(num) => Ok((Token::Int(num), len)),
+                // Literals of any length are allowed, they just become big integers
+                Err(err) if err.kind() == &IntErrorKind::PosOverflow => {
+                    match num_string.parse::<BigInt>() {
+                        Ok(num) => Ok((Token::BigInt(num), len)),
+                        Err(_) => Err(LexerError::IntError(err.kind().to_owned())),
+                    }
+                }
                 Err(err) => Err(LexerError::IntError(err.kind().to_owned())),
             },
 
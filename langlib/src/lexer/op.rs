@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 
 pub enum BinOp {
@@ -13,6 +15,33 @@ pub enum BinOp {
     LessEqSign,
     And,
     Or,
+    /// Membership (e.g `"a" in map`).
+    In,
+    /// Null-coalescing (e.g `name ?? "anon"`), which only evaluates the right side if the left side is null.
+    Coalesce,
+}
+
+impl Display for BinOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::EqSign => "==",
+            BinOp::NeqSign => "!=",
+            BinOp::GreaterSign => ">",
+            BinOp::LessSign => "<",
+            BinOp::GreaterEqSign => ">=",
+            BinOp::LessEqSign => "<=",
+            BinOp::And => "and",
+            BinOp::Or => "or",
+            BinOp::In => "in",
+            BinOp::Coalesce => "??",
+        };
+
+        write!(f, "{op}")
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -21,3 +50,12 @@ pub enum UnOp {
     Bang,
     Minus,
 }
+
+impl Display for UnOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnOp::Bang => write!(f, "!"),
+            UnOp::Minus => write!(f, "-"),
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt::Display;
+
+/// A region of the source code.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Span {
+    /// The byte offset of the start of the region.
+    pub start: usize,
+    /// The byte offset just past the end of the region.
+    pub end: usize,
+    /// The line the region starts on, counting from 1.
+    pub line: usize,
+    /// The column the region starts at, counting from 1.
+    pub col: usize,
+}
+
+impl Span {
+    /// Returns a span covering both `self` and everything up to the end of `other`.
+    pub fn to(self, other: Span) -> Span {
+        Span {
+            end: other.end,
+            ..self
+        }
+    }
+}
+
+/// Describes where an error happened, if that's known.
+pub(crate) fn location(span: &Option<Span>) -> String {
+    match span {
+        Some(span) => format!(" at {span}"),
+        None => String::new(),
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.col)
+    }
+}
@@ -2,7 +2,9 @@
 mod lexer_tokenizer_tests {
 
     use crate::lexer::{
+        err::LexerError,
         op::BinOp,
+        span::Span,
         token::{Keyword, Token},
         Lexer,
     };
@@ -29,11 +31,14 @@ mod lexer_tokenizer_tests {
 
         assert_eq!(num_token.0, Token::Int(123456789));
 
-        let overflow_num = "123456785435643829043568";
+        let big_num = "123456785435643829043568";
 
-        let overflow_num_token = Lexer::parse_token(overflow_num);
+        let big_num_token = Lexer::parse_token(big_num);
 
-        assert!(overflow_num_token.is_err());
+        assert_eq!(
+            big_num_token.unwrap().0,
+            Token::BigInt(big_num.parse().unwrap())
+        );
     }
 
     #[test]
@@ -128,4 +133,99 @@ mod lexer_tokenizer_tests {
         let result = lexer.tokenize();
         assert_eq!(result.unwrap(), vec![Token::Op(BinOp::LessEqSign)]);
     }
+
+    #[test]
+    fn test_arrow() {
+        let mut lexer = Lexer::new("(a) => a");
+        let result = lexer.tokenize();
+        assert_eq!(
+            result.unwrap(),
+            vec![
+                Token::LeftBracket,
+                Token::Ident("a".to_owned()),
+                Token::RightBracket,
+                Token::Arrow,
+                Token::Ident("a".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spans() {
+        let mut lexer = Lexer::new("let a = 1;\n  print a;");
+        let (tokens, spans) = lexer.tokenize_with_spans().unwrap();
+
+        assert_eq!(tokens.len(), spans.len());
+        assert_eq!(
+            spans[0],
+            Span {
+                start: 0,
+                end: 3,
+                line: 1,
+                col: 1
+            }
+        );
+        assert_eq!(
+            spans[5],
+            Span {
+                start: 13,
+                end: 18,
+                line: 2,
+                col: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut lexer = Lexer::new("0..10 0..=10 ...");
+        assert_eq!(
+            lexer.tokenize().unwrap(),
+            vec![
+                Token::Int(0),
+                Token::DotDot,
+                Token::Int(10),
+                Token::Int(0),
+                Token::DotDotEq,
+                Token::Int(10),
+                Token::Ellipsis
+            ]
+        );
+    }
+
+    #[test]
+    fn test_underscore_idents() {
+        let mut lexer = Lexer::new("__add__ snake_case");
+        assert_eq!(
+            lexer.tokenize().unwrap(),
+            vec![
+                Token::Ident("__add__".to_owned()),
+                Token::Ident("snake_case".to_owned())
+            ]
+        );
+
+        assert_eq!(
+            Lexer::new("a @ b").tokenize(),
+            Err(LexerError::InvalidChar('@'))
+        );
+    }
+
+    #[test]
+    fn test_question_marks() {
+        let mut lexer = Lexer::new("a ? b : c ?? null?.d");
+        assert_eq!(
+            lexer.tokenize().unwrap(),
+            vec![
+                Token::Ident("a".to_owned()),
+                Token::Question,
+                Token::Ident("b".to_owned()),
+                Token::Colon,
+                Token::Ident("c".to_owned()),
+                Token::Op(BinOp::Coalesce),
+                Token::Keyword(Keyword::Null),
+                Token::QuestionDot,
+                Token::Ident("d".to_owned()),
+            ]
+        );
+    }
 }
@@ -1,6 +1,6 @@
 use thiserror::Error;
 
-use crate::expr::Expr;
+use crate::{bigint::BigInt, expr::Expr};
 
 use super::op::{BinOp, UnOp};
 
@@ -11,13 +11,30 @@ pub enum Token {
     DeclarationSign,
     EqSign,
     Int(i32),
+    /// An integer literal too big for an `i32`.
+    BigInt(BigInt),
     Semi,
+    Colon,
     Comma,
+    Arrow,
+    Ellipsis,
+    /// `..`, used for ranges.
+    DotDot,
+    /// `..=`, used for inclusive ranges.
+    DotDotEq,
+    /// `.`, used for property access.
+    Dot,
+    /// `?`, used for conditional expressions.
+    Question,
+    /// `?.`, used for optional chaining.
+    QuestionDot,
     String(String),
     LeftBracket,
     RightBracket,
     LeftCurly,
     RightCurly,
+    LeftSquare,
+    RightSquare,
     Ident(String),
     Keyword(Keyword),
 }
@@ -66,11 +83,13 @@ impl Token {
     pub fn into_expr(self) -> Result<Expr, TokenError> {
         match self {
             Token::Int(int) => Ok(Expr::Num(int)),
+            Token::BigInt(int) => Ok(Expr::BigNum(int)),
             Token::String(string) => Ok(Expr::Str(string)),
             Token::Keyword(keyword) => match keyword {
                 Keyword::True => Ok(Expr::Bool(true)),
                 Keyword::False => Ok(Expr::Bool(false)),
-                _ => Ok(Expr::Null),
+                Keyword::Null => Ok(Expr::Null),
+                _ => Err(TokenError::FailedConversion),
             },
             _ => Err(TokenError::FailedConversion),
         }
@@ -89,6 +108,8 @@ pub enum Keyword {
     True,
     False,
     Let,
+    Const,
+    Null,
     If,
     Else,
     Print,
@@ -96,10 +117,18 @@ pub enum Keyword {
     For,
     Func,
     Return,
+    Break,
+    Continue,
+    Class,
+    This,
+    Super,
+    Trait,
+    Impl,
+    Enum,
+    Match,
 }
 
 #[cfg(test)]
-
 mod token_tests {
     use crate::{
         expr::Expr,
@@ -147,6 +176,10 @@ mod token_tests {
         let num = Token::Int(23).into_expr();
         let true_bool = Token::Keyword(Keyword::True).into_expr();
         let false_bool = Token::Keyword(Keyword::False).into_expr();
+        let null = Token::Keyword(Keyword::Null).into_expr();
+
+        assert_eq!(null, Ok(Expr::Null));
+        assert!(Token::Keyword(Keyword::Let).into_expr().is_err());
 
         assert!(string.is_ok());
         assert!(num.is_ok());
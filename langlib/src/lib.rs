@@ -1,9 +1,16 @@
 #![feature(iterator_try_collect)]
 #![feature(try_find)]
 
+pub mod bigint;
+pub mod class;
+pub mod enums;
 pub mod expr;
 pub mod func;
 pub mod interpreter;
 pub mod lexer;
+pub mod map;
 pub mod parser;
+pub mod pattern;
+pub mod resolver;
 pub mod stmt;
+pub mod value;
@@ -0,0 +1,106 @@
+use std::fmt::Display;
+
+use ahash::AHashMap;
+
+use crate::{bigint::BigInt, interpreter::err::RuntimeErr, value::Value};
+
+/// A map that remembers the order its keys were first inserted in.
+#[derive(Debug, Clone, Default)]
+pub struct Map {
+    entries: Vec<(Key, Value)>,
+    /// The position of each key in `entries`.
+    indices: AHashMap<Key, usize>,
+}
+
+impl Map {
+    /// Gets the value stored under `key`.
+    pub fn get(&self, key: &Key) -> Option<&Value> {
+        self.indices.get(key).map(|&i| &self.entries[i].1)
+    }
+
+    /// Stores a value under `key`. Replacing the value of an existing key keeps its position.
+    pub fn insert(&mut self, key: Key, v: Value) {
+        match self.indices.get(&key) {
+            Some(&i) => self.entries[i].1 = v,
+            None => {
+                self.indices.insert(key.clone(), self.entries.len());
+                self.entries.push((key, v));
+            }
+        }
+    }
+
+    pub fn contains_key(&self, key: &Key) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the entries in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Key, &Value)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+}
+
+impl FromIterator<(Key, Value)> for Map {
+    fn from_iter<T: IntoIterator<Item = (Key, Value)>>(iter: T) -> Self {
+        let mut map = Map::default();
+
+        for (k, v) in iter {
+            map.insert(k, v);
+        }
+
+        map
+    }
+}
+
+/// A value that can be used as a map key. Only values that can't change and have a sensible notion of equality
+/// are allowed.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Key {
+    Int(i32),
+    BigInt(BigInt),
+    Str(String),
+    Bool(bool),
+}
+
+impl TryFrom<&Value> for Key {
+    type Error = RuntimeErr;
+
+    fn try_from(v: &Value) -> Result<Self, Self::Error> {
+        match v {
+            Value::Int(num) => Ok(Key::Int(*num)),
+            Value::BigInt(num) => Ok(Key::BigInt(num.clone())),
+            Value::Str(string) => Ok(Key::Str(string.clone())),
+            Value::Bool(bool) => Ok(Key::Bool(*bool)),
+            v => Err(RuntimeErr::UnhashableKey(v.type_of())),
+        }
+    }
+}
+
+impl From<Key> for Value {
+    fn from(key: Key) -> Self {
+        match key {
+            Key::Int(num) => Value::Int(num),
+            Key::BigInt(num) => Value::BigInt(num),
+            Key::Str(string) => Value::Str(string),
+            Key::Bool(bool) => Value::Bool(bool),
+        }
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Key::Int(num) => write!(f, "{num}"),
+            Key::BigInt(num) => write!(f, "{num}"),
+            Key::Str(string) => write!(f, "\"{string}\""),
+            Key::Bool(bool) => write!(f, "{bool}"),
+        }
+    }
+}
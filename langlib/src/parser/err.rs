@@ -1,7 +1,6 @@
 use thiserror::Error;
 
 use crate::{
-    expr::ExprError,
     lexer::token::{Token, TokenError},
     stmt::StmtErr,
 };
@@ -18,9 +17,6 @@ pub enum ParserError {
     #[error("A statement error has occured while parsing")]
     StmtErr(#[from] StmtErr),
 
-    #[error("An expression error has occured while parsing")]
-    ExprError(#[from] ExprError),
-
     #[error("An incomplete term was encountered while parsing.")]
     BadTerm(usize),
 
@@ -51,6 +47,29 @@ pub enum ParserError {
     #[error("Only a maximum capacity of 254 arguments is supported.")]
     TooManyArgs(usize),
 
+    #[error("Positional arguments can't come after named arguments.")]
+    PositionalAfterNamedArg(usize),
+
+    #[error("The rest parameter must be the last parameter of a function.")]
+    RestParamNotLast(usize),
+
+    #[error(
+        "The parameter \"{0}\" must have a default value since it follows a parameter with one."
+    )]
+    RequiredParamAfterDefault(String, usize),
+
+    #[error("A for-in loop takes one or two loop variables.")]
+    InvalidLoopVars(usize),
+
+    #[error("Only variables, list elements and properties can be assigned to.")]
+    InvalidAssignmentTarget(usize),
+
+    #[error("An invalid pattern was found.")]
+    InvalidPattern(usize),
+
+    #[error("The rest pattern must be the last item of a list pattern.")]
+    RestPatternNotLast(usize),
+
     #[error("The parser failed to match the rule: {0:?}")]
     FailedRuleMatch(Vec<Token>, usize),
 }
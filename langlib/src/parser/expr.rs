@@ -1,9 +1,11 @@
 use crate::{
-    expr::{BinExpr, Expr},
+    expr::{BinExpr, Expr, Var},
+    func::Func,
     lexer::{
         op::{BinOp, UnOp},
         token::Token,
     },
+    stmt::Stmt,
 };
 
 use super::{err::ParserError, Parser};
@@ -11,11 +13,53 @@ use super::{err::ParserError, Parser};
 impl Parser {
     /// Attempts to parse an expression.
     pub fn expr(&mut self) -> Result<Expr, ParserError> {
-        self.logical_or()
+        self.ternary()
+    }
+
+    /// Attempts to parse a conditional expression. It binds looser than every operator, and nests to the right, so
+    /// `a ? b : c ? d : e` is `a ? b : (c ? d : e)`.
+    pub fn ternary(&mut self) -> Result<Expr, ParserError> {
+        let condition = self.coalesce()?;
+
+        if !self.match_rule(&[Token::Question]) {
+            return Ok(condition);
+        }
+
+        let then_branch = self.expr()?;
+
+        self.expect_consume(&[Token::Colon])?;
+
+        let else_branch = self.ternary()?;
+
+        Ok(Expr::Ternary(
+            Box::new(condition),
+            Box::new(then_branch),
+            Box::new(else_branch),
+        ))
+    }
+
+    /// Attempts to parse a null-coalescing expression.
+    pub fn coalesce(&mut self) -> Result<Expr, ParserError> {
+        let start = self.cursor;
+        let mut lhs = self.logical_or()?;
+
+        while let Some(op) = self.matches(&[Token::Op(BinOp::Coalesce)]) {
+            let rhs = self.logical_or()?;
+
+            lhs = Expr::Bin(BinExpr {
+                lhs: Box::new(lhs),
+                rhs: Box::new(rhs),
+                op: op.try_into_op()?,
+                span: self.span_from(start),
+            });
+        }
+
+        Ok(lhs)
     }
 
     /// Attempts to parse a logical `or` expession.
     pub fn logical_or(&mut self) -> Result<Expr, ParserError> {
+        let start = self.cursor;
         let mut lhs = self.logical_and()?;
 
         while let Some(op) = self.matches(&[Token::Op(BinOp::Or)]) {
@@ -25,6 +69,7 @@ impl Parser {
                 lhs: Box::new(lhs),
                 rhs: Box::new(rhs),
                 op: op.try_into_op()?,
+                span: self.span_from(start),
             });
         }
 
@@ -33,6 +78,7 @@ impl Parser {
 
     /// Attempts to parse a logical `and` expession.
     pub fn logical_and(&mut self) -> Result<Expr, ParserError> {
+        let start = self.cursor;
         let mut lhs = self.equality()?;
 
         while let Some(op) = self.matches(&[Token::Op(BinOp::And)]) {
@@ -42,6 +88,7 @@ impl Parser {
                 lhs: Box::new(lhs),
                 rhs: Box::new(rhs),
                 op: op.try_into_op()?,
+                span: self.span_from(start),
             });
         }
 
@@ -50,6 +97,7 @@ impl Parser {
 
     /// Attempts to parse a compare expression.
     pub fn equality(&mut self) -> Result<Expr, ParserError> {
+        let start = self.cursor;
         let mut lhs = self.comparision()?;
 
         while let Some(op) = self.matches(&[Token::Op(BinOp::EqSign), Token::Op(BinOp::NeqSign)]) {
@@ -59,6 +107,7 @@ impl Parser {
                 lhs: Box::new(lhs),
                 rhs: Box::new(rhs),
                 op: op.try_into_op()?,
+                span: self.span_from(start),
             });
         }
 
@@ -67,28 +116,50 @@ impl Parser {
 
     /// Attempts to parse a comparision expression.
     pub fn comparision(&mut self) -> Result<Expr, ParserError> {
-        let mut lhs = self.term()?;
+        let start = self.cursor;
+        let mut lhs = self.range()?;
 
         while let Some(op) = self.matches(&[
             Token::Op(BinOp::GreaterSign),
             Token::Op(BinOp::GreaterEqSign),
             Token::Op(BinOp::LessSign),
             Token::Op(BinOp::LessEqSign),
+            Token::Op(BinOp::In),
         ]) {
-            let rhs = self.term()?;
+            let rhs = self.range()?;
 
             lhs = Expr::Bin(BinExpr {
                 lhs: Box::new(lhs),
                 rhs: Box::new(rhs),
                 op: op.try_into_op()?,
+                span: self.span_from(start),
             });
         }
 
         Ok(lhs)
     }
 
+    /// Attempts to parse a range (e.g `0..10` or `0..=10`). Ranges can't be chained.
+    pub fn range(&mut self) -> Result<Expr, ParserError> {
+        let lhs = self.term()?;
+
+        match self.matches(&[Token::DotDot, Token::DotDotEq]) {
+            Some(token) => {
+                let rhs = self.term()?;
+
+                Ok(Expr::Range(
+                    Box::new(lhs),
+                    Box::new(rhs),
+                    token == Token::DotDotEq,
+                ))
+            }
+            None => Ok(lhs),
+        }
+    }
+
     /// Attempts to parse a term.
     pub fn term(&mut self) -> Result<Expr, ParserError> {
+        let start = self.cursor;
         let mut lhs = self.factor()?;
 
         while let Some(op) = self.matches(&[Token::Op(BinOp::Add), Token::Op(BinOp::Sub)]) {
@@ -98,6 +169,7 @@ impl Parser {
                 lhs: Box::new(lhs),
                 rhs: Box::new(rhs),
                 op: op.try_into_op()?,
+                span: self.span_from(start),
             });
         }
 
@@ -106,6 +178,7 @@ impl Parser {
 
     /// Attempts to parse a factor.
     pub fn factor(&mut self) -> Result<Expr, ParserError> {
+        let start = self.cursor;
         let mut lhs = self.unary()?;
 
         while let Some(op) = self.matches(&[Token::Op(BinOp::Mul), Token::Op(BinOp::Div)]) {
@@ -115,6 +188,7 @@ impl Parser {
                 lhs: Box::new(lhs),
                 rhs: Box::new(rhs),
                 op: op.try_into_op()?,
+                span: self.span_from(start),
             });
         }
 
@@ -123,35 +197,129 @@ impl Parser {
 
     /// Attempts to parse a unary expression.
     pub fn unary(&mut self) -> Result<Expr, ParserError> {
+        let start = self.cursor;
+
         if self.match_rule(&[Token::UnOp(UnOp::Bang)]) {
             let expr = self.primary()?;
-            return Ok(Expr::Unary(UnOp::Bang, Box::new(expr)));
+            return Ok(Expr::Unary(
+                UnOp::Bang,
+                Box::new(expr),
+                self.span_from(start),
+            ));
+        }
+
+        // A `-` in front of an operand negates it (e.g `xs[-1]`)
+        if self.match_rule(&[Token::Op(BinOp::Sub)]) {
+            let expr = self.unary()?;
+            return Ok(Expr::Unary(
+                UnOp::Minus,
+                Box::new(expr),
+                self.span_from(start),
+            ));
         }
 
         self.funcall()
     }
 
-    /// Attempts to parse a function call.
+    /// Attempts to parse a function call, an index or slice, or a property access. Any of these can be chained, and
+    /// chains with an optional link (e.g `a?.b`, `f?.()`) are wrapped in an `Expr::Chain`.
     pub fn funcall(&mut self) -> Result<Expr, ParserError> {
         let mut expr = self.primary()?;
+        let mut optional = false;
 
         loop {
             if self.match_rule(&[Token::LeftBracket]) {
                 expr = self.parse_args(expr)?;
+            } else if self.match_rule(&[Token::LeftSquare]) {
+                expr = self.parse_index(expr)?;
+            } else if self.match_rule(&[Token::Dot]) {
+                let ident = self.curr()?.try_into_ident()?;
+                self.adv();
+
+                expr = Expr::Get(Box::new(expr), ident);
+            } else if self.match_rule(&[Token::QuestionDot]) {
+                optional = true;
+
+                expr = if self.match_rule(&[Token::LeftBracket]) {
+                    match self.parse_args(expr)? {
+                        Expr::Funcall(callee, args, named) => {
+                            Expr::OptionalCall(callee, args, named)
+                        }
+                        _ => unreachable!("arguments always make a call"),
+                    }
+                } else {
+                    let ident = self.curr()?.try_into_ident()?;
+                    self.adv();
+
+                    Expr::OptionalGet(Box::new(expr), ident)
+                };
             } else {
                 break;
             }
         }
 
+        if optional {
+            return Ok(Expr::Chain(Box::new(expr)));
+        }
+
         Ok(expr)
     }
 
+    /// Attempts to parse what's inside the square brackets of an index (e.g `[i]`) or a slice (e.g `[1:3]`).
+    fn parse_index(&mut self, target: Expr) -> Result<Expr, ParserError> {
+        // A slice can leave out its start (e.g `[:2]`)
+        if self.match_rule(&[Token::Colon]) {
+            return self.parse_slice(target, None);
+        }
+
+        if self.curr()? == Token::RightSquare {
+            return Err(ParserError::ExpectedExpr(self.cursor));
+        }
+
+        let index = self.expr()?;
+
+        if self.match_rule(&[Token::Colon]) {
+            return self.parse_slice(target, Some(Box::new(index)));
+        }
+
+        self.expect_consume(&[Token::RightSquare])?;
+
+        Ok(Expr::Index(Box::new(target), Box::new(index)))
+    }
+
+    /// Attempts to parse the end of a slice, after the `:`.
+    fn parse_slice(&mut self, target: Expr, start: Option<Box<Expr>>) -> Result<Expr, ParserError> {
+        let end = match self.curr()? {
+            Token::RightSquare => None,
+            _ => Some(Box::new(self.expr()?)),
+        };
+
+        self.expect_consume(&[Token::RightSquare])?;
+
+        Ok(Expr::Slice(Box::new(target), start, end))
+    }
+
+    /// Attempts to parse the arguments of a function call. Named arguments (e.g `retries: 3`) can only come
+    /// after all of the positional ones.
     fn parse_args(&mut self, callee: Expr) -> Result<Expr, ParserError> {
         let mut args: Vec<Expr> = Vec::new();
+        let mut named: Vec<(String, Expr)> = Vec::new();
 
         if self.curr()? != Token::RightBracket {
             loop {
-                args.push(self.expr()?);
+                if self.is_named_arg() {
+                    let ident = self.curr()?.try_into_ident()?;
+
+                    // Skip the identifier and the `:`
+                    self.adv();
+                    self.adv();
+
+                    named.push((ident, self.expr()?));
+                } else if named.is_empty() {
+                    args.push(self.expr()?);
+                } else {
+                    return Err(ParserError::PositionalAfterNamedArg(self.cursor));
+                }
 
                 if !(self.match_rule(&[Token::Comma])) {
                     break;
@@ -159,13 +327,19 @@ impl Parser {
             }
         }
 
-        if args.len() > 255 {
+        if args.len() + named.len() > 255 {
             return Err(ParserError::TooManyArgs(self.cursor));
         }
 
         self.adv();
 
-        Ok(Expr::Funcall(Box::new(callee), args))
+        Ok(Expr::Funcall(Box::new(callee), args, named))
+    }
+
+    /// Checks if the tokens at the cursor are the start of a named argument (e.g `retries: 3`).
+    fn is_named_arg(&self) -> bool {
+        matches!(self.tokens.get(self.cursor), Some(Token::Ident(_)))
+            && self.tokens.get(self.cursor + 1) == Some(&Token::Colon)
     }
 
     /// Attempts to parse a "primary". A primary is a type similiar to a literal, however a primary can include things
@@ -176,15 +350,20 @@ impl Parser {
                 self.adv();
                 Ok(Expr::Num(int))
             }
+            Token::BigInt(int) => {
+                self.adv();
+                Ok(Expr::BigNum(int))
+            }
             Token::String(str) => {
                 self.adv();
 
                 Ok(Expr::Str(str))
             }
             Token::Ident(ident) => {
+                let var = Var::with_span(&ident, self.span_at(self.cursor));
                 self.adv();
 
-                Ok(Expr::Var(ident))
+                Ok(Expr::Var(var))
             }
             Token::Keyword(keyword) => match keyword {
                 crate::lexer::token::Keyword::True => {
@@ -197,12 +376,46 @@ impl Parser {
 
                     Ok(Expr::Bool(false))
                 }
+                crate::lexer::token::Keyword::Null => {
+                    self.adv();
+
+                    Ok(Expr::Null)
+                }
+                crate::lexer::token::Keyword::Func => {
+                    self.adv();
+
+                    Ok(Expr::Func(self.func_body()?))
+                }
+                // `this` is resolved like any other variable
+                crate::lexer::token::Keyword::This => {
+                    let var = Var::with_span("this", self.span_at(self.cursor));
+                    self.adv();
+
+                    Ok(Expr::Var(var))
+                }
+                crate::lexer::token::Keyword::Match => self.match_expr(),
+                // `super` can only be used to get a method
+                crate::lexer::token::Keyword::Super => {
+                    let span = self.span_at(self.cursor);
+                    self.adv();
+                    self.expect_consume(&[Token::Dot])?;
+
+                    let ident = self.curr()?.try_into_ident()?;
+                    self.adv();
+
+                    Ok(Expr::Super(Var::with_span("super", span), ident))
+                }
                 _ => Err(ParserError::UnexpectedToken(
                     Token::Keyword(keyword),
                     self.cursor,
                 )),
             },
 
+            Token::LeftBracket if self.is_arrow_func() => self.arrow_func(),
+
+            Token::LeftSquare => self.list(),
+            Token::LeftCurly => self.map(),
+
             // Attempt to parse an expression wrapped in brackets
             _ => {
                 self.expect_consume(&[Token::LeftBracket])?;
@@ -214,4 +427,80 @@ impl Parser {
             }
         }
     }
+
+    /// Attempts to parse a list literal (e.g `[1, 2, 3]`). A trailing comma is allowed.
+    fn list(&mut self) -> Result<Expr, ParserError> {
+        self.expect_consume(&[Token::LeftSquare])?;
+
+        let mut items = Vec::new();
+
+        while self.curr()? != Token::RightSquare {
+            items.push(self.expr()?);
+
+            if !self.match_rule(&[Token::Comma]) {
+                break;
+            }
+        }
+
+        self.expect_consume(&[Token::RightSquare])?;
+
+        Ok(Expr::List(items))
+    }
+
+    /// Attempts to parse a map literal (e.g `{"a": 1, "b": 2}`). A trailing comma is allowed.
+    pub(super) fn map(&mut self) -> Result<Expr, ParserError> {
+        self.expect_consume(&[Token::LeftCurly])?;
+
+        let mut entries = Vec::new();
+
+        while self.curr()? != Token::RightCurly {
+            let key = self.expr()?;
+
+            self.expect_consume(&[Token::Colon])?;
+
+            entries.push((key, self.expr()?));
+
+            if !self.match_rule(&[Token::Comma]) {
+                break;
+            }
+        }
+
+        self.expect_consume(&[Token::RightCurly])?;
+
+        Ok(Expr::Map(entries))
+    }
+
+    /// Checks if the curly bracket at the cursor is the start of a map literal rather than a block, by looking for
+    /// a `:` after the first key. Keys are usually a single token (e.g `{"a": 1}`), while no statement starts with
+    /// a token followed by a `:`.
+    pub(super) fn is_map(&self) -> bool {
+        self.tokens.get(self.cursor + 2) == Some(&Token::Colon)
+    }
+
+    /// Attempts to parse an arrow function (e.g `(a, b) => a + b`). The body can either be a single expression
+    /// or a block.
+    fn arrow_func(&mut self) -> Result<Expr, ParserError> {
+        let (args, rest) = self.params()?;
+
+        self.expect_consume(&[Token::Arrow])?;
+
+        // An expression body is the same as a block that returns it.
+        let body = if self.curr()? == Token::LeftCurly {
+            self.block()?
+        } else {
+            Stmt::Block(vec![Stmt::Return(self.expr()?, None)])
+        };
+
+        Ok(Expr::Func(Func::new(body, args, rest)))
+    }
+
+    /// Checks if the bracket at the cursor is the start of an arrow function's parameter list, by looking
+    /// for a `=>` right after the matching closing bracket.
+    fn is_arrow_func(&self) -> bool {
+        self.closing_brackets
+            .get(self.cursor)
+            .copied()
+            .flatten()
+            .is_some_and(|end| self.tokens.get(end + 1) == Some(&Token::Arrow))
+    }
 }
@@ -1,5 +1,6 @@
 pub mod err;
 mod expr;
+mod pattern;
 mod stmt;
 mod tests;
 
@@ -7,16 +8,31 @@ use crate::stmt::Stmt;
 
 use self::err::ParserError;
 
-use super::lexer::token::Token;
+use super::lexer::{span::Span, token::Token};
 
 pub struct Parser {
     tokens: Vec<Token>,
+    /// Where each token is in the source. This is empty if the parser was only given tokens.
+    spans: Vec<Span>,
+    /// The index of the matching `)` for every `(`, so checking what follows a bracketed group doesn't have to
+    /// scan through it.
+    closing_brackets: Vec<Option<usize>>,
     cursor: usize,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, cursor: 0 }
+        Self::with_spans(tokens, Vec::new())
+    }
+
+    /// Creates a parser that also records where expressions are in the source, so errors can point at them.
+    pub fn with_spans(tokens: Vec<Token>, spans: Vec<Span>) -> Self {
+        Self {
+            closing_brackets: closing_brackets(&tokens),
+            tokens,
+            spans,
+            cursor: 0,
+        }
     }
 
     pub fn get_statements(&mut self) -> Result<Vec<Stmt>, ParserError> {
@@ -144,6 +160,21 @@ impl Parser {
         Ok(self.tokens[i].to_owned())
     }
 
+    /// Returns the span of the token at `i`, if the parser was given spans.
+    fn span_at(&self, i: usize) -> Option<Span> {
+        self.spans.get(i).copied()
+    }
+
+    /// Returns the span from the token at `start` up to the last token that was consumed.
+    fn span_from(&self, start: usize) -> Option<Span> {
+        // Skip over any semicolons that were consumed along with the last token
+        let end = (start..self.cursor)
+            .rev()
+            .find(|&i| self.tokens[i] != Token::Semi)?;
+
+        Some(self.spans.get(start)?.to(*self.spans.get(end)?))
+    }
+
     /// Returns a boolean indicating whether the position is at the end of the token stream.
     pub fn is_at_end(&self) -> bool {
         self.cursor >= self.tokens.len()
@@ -157,3 +188,24 @@ impl Parser {
         Ok(())
     }
 }
+
+/// Pairs up the brackets in the tokens, returning the index of the matching `)` for every `(`. Unclosed brackets
+/// are left as `None`.
+fn closing_brackets(tokens: &[Token]) -> Vec<Option<usize>> {
+    let mut closing = vec![None; tokens.len()];
+    let mut open = Vec::new();
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::LeftBracket => open.push(i),
+            Token::RightBracket => {
+                if let Some(start) = open.pop() {
+                    closing[start] = Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    closing
+}
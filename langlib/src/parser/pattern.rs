@@ -0,0 +1,231 @@
+use crate::{
+    expr::{Expr, Var},
+    lexer::{
+        op::{BinOp, UnOp},
+        token::{Keyword, Token},
+    },
+    pattern::{Arm, Binding, Match, Pattern},
+    stmt::Stmt,
+};
+
+use super::{err::ParserError, Parser};
+
+impl Parser {
+    /// Attempts to parse a `match` used as an expression. Every arm has to be an expression.
+    pub(super) fn match_expr(&mut self) -> Result<Expr, ParserError> {
+        let parsed = self.match_arms(|parser| parser.expr())?;
+
+        Ok(Expr::Match(Box::new(parsed)))
+    }
+
+    /// Attempts to parse a `match` used as a statement. Arms can also be blocks, which are allowed to return from
+    /// the enclosing function or break out of the enclosing loop.
+    pub(super) fn match_stmt(&mut self) -> Result<Stmt, ParserError> {
+        let parsed = self.match_arms(|parser| match parser.curr()? {
+            Token::LeftCurly if !parser.is_map() => parser.block(),
+            _ => Ok(Stmt::Expr(parser.expr()?)),
+        })?;
+
+        Ok(Stmt::Match(parsed))
+    }
+
+    /// Parses the value and arms of a `match` (e.g `match (v) { 1 => "one", n if n > 1 => "many", _ => "none" }`).
+    /// Arms are separated by commas, and a trailing comma is allowed.
+    fn match_arms<B>(
+        &mut self,
+        mut body: impl FnMut(&mut Self) -> Result<B, ParserError>,
+    ) -> Result<Match<B>, ParserError> {
+        self.expect_consume(&[Token::Keyword(Keyword::Match), Token::LeftBracket])?;
+
+        let val = self.expr()?;
+
+        self.expect_consume(&[Token::RightBracket, Token::LeftCurly])?;
+
+        let mut arms = Vec::new();
+
+        while self.curr()? != Token::RightCurly {
+            let pattern = self.pattern()?;
+
+            let guard = if self.match_rule(&[Token::Keyword(Keyword::If)]) {
+                Some(self.expr()?)
+            } else {
+                None
+            };
+
+            self.expect_consume(&[Token::Arrow])?;
+
+            arms.push(Arm {
+                pattern,
+                guard,
+                body: body(self)?,
+            });
+
+            // Arms ending in a block don't need a comma after them
+            if !self.match_rule(&[Token::Comma]) && self.prev()? != Token::RightCurly {
+                break;
+            }
+        }
+
+        self.expect_consume(&[Token::RightCurly])?;
+
+        Ok(Match { val, arms })
+    }
+
+    /// Attempts to parse a pattern.
+    pub(super) fn pattern(&mut self) -> Result<Pattern, ParserError> {
+        match self.curr()? {
+            Token::Ident(ident) if ident == "_" => {
+                self.adv();
+
+                Ok(Pattern::Wildcard)
+            }
+            // A name followed by a `.` is a variant of an enum
+            Token::Ident(ident) if self.at(self.cursor + 1) == Ok(Token::Dot) => {
+                let enum_var = Var::with_span(&ident, self.span_at(self.cursor));
+                self.adv();
+                self.adv();
+
+                let variant = self.curr()?.try_into_ident()?;
+                self.adv();
+
+                let fields = if self.match_rule(&[Token::LeftBracket]) {
+                    match self.patterns(Token::RightBracket)? {
+                        (fields, None) => Some(fields),
+                        // Variants always hold a fixed number of values
+                        (_, Some(_)) => return Err(ParserError::InvalidPattern(self.cursor)),
+                    }
+                } else {
+                    None
+                };
+
+                Ok(Pattern::Variant {
+                    enum_var,
+                    variant,
+                    fields,
+                })
+            }
+            Token::Ident(ident) => {
+                let span = self.span_at(self.cursor);
+                self.adv();
+
+                Ok(Pattern::Binding(Binding::with_span(&ident, span)))
+            }
+            Token::Int(_)
+            | Token::BigInt(_)
+            | Token::String(_)
+            | Token::Keyword(Keyword::True | Keyword::False | Keyword::Null) => {
+                Ok(Pattern::Literal(self.primary()?))
+            }
+            Token::Op(BinOp::Sub) => {
+                self.adv();
+
+                match self.curr()? {
+                    Token::Int(_) | Token::BigInt(_) => Ok(Pattern::Literal(Expr::Unary(
+                        UnOp::Minus,
+                        Box::new(self.primary()?),
+                        None,
+                    ))),
+                    _ => Err(ParserError::InvalidPattern(self.cursor)),
+                }
+            }
+            Token::LeftSquare => {
+                self.adv();
+
+                let (items, rest) = self.patterns(Token::RightSquare)?;
+
+                Ok(Pattern::List {
+                    items,
+                    rest: rest.map(Box::new),
+                })
+            }
+            Token::LeftCurly => {
+                self.adv();
+
+                let mut entries = Vec::new();
+
+                while self.curr()? != Token::RightCurly {
+                    let entry = match self.curr()? {
+                        // A name on its own is short for binding the value of the key with the same name
+                        // (e.g `{name}` is `{"name": name}`)
+                        Token::Ident(ident) => {
+                            let span = self.span_at(self.cursor);
+                            self.adv();
+
+                            let binding = Pattern::Binding(Binding::with_span(&ident, span));
+
+                            (Expr::Str(ident), self.default_pattern(binding)?)
+                        }
+                        Token::Int(_)
+                        | Token::BigInt(_)
+                        | Token::String(_)
+                        | Token::Keyword(Keyword::True | Keyword::False) => {
+                            let key = self.primary()?;
+
+                            self.expect_consume(&[Token::Colon])?;
+
+                            let pattern = self.pattern()?;
+
+                            (key, self.default_pattern(pattern)?)
+                        }
+                        _ => return Err(ParserError::InvalidPattern(self.cursor)),
+                    };
+
+                    entries.push(entry);
+
+                    if !self.match_rule(&[Token::Comma]) {
+                        break;
+                    }
+                }
+
+                self.expect_consume(&[Token::RightCurly])?;
+
+                Ok(Pattern::Map(entries))
+            }
+            _ => Err(ParserError::InvalidPattern(self.cursor)),
+        }
+    }
+
+    /// Parses the default value of a list item or map entry if there is one (e.g the `= 0` in `[a, b = 0]`).
+    fn default_pattern(&mut self, pattern: Pattern) -> Result<Pattern, ParserError> {
+        if self.match_rule(&[Token::DeclarationSign]) {
+            Ok(Pattern::Default(Box::new(pattern), self.expr()?))
+        } else {
+            Ok(pattern)
+        }
+    }
+
+    /// Parses patterns separated by commas up to `end`, which is consumed too. The last pattern can be a rest
+    /// pattern (e.g `...rest` or just `...`), which is returned separately.
+    fn patterns(&mut self, end: Token) -> Result<(Vec<Pattern>, Option<Pattern>), ParserError> {
+        let mut patterns = Vec::new();
+        let mut rest = None;
+
+        while self.curr()? != end {
+            // Nothing is allowed to come after the rest pattern
+            if rest.is_some() {
+                return Err(ParserError::RestPatternNotLast(self.cursor));
+            }
+
+            if self.match_rule(&[Token::Ellipsis]) {
+                // A rest pattern on its own ignores the items that are left over
+                rest = match self.curr()? {
+                    Token::Comma => Some(Pattern::Wildcard),
+                    token if token == end => Some(Pattern::Wildcard),
+                    _ => Some(self.pattern()?),
+                };
+            } else {
+                let pattern = self.pattern()?;
+
+                patterns.push(self.default_pattern(pattern)?);
+            }
+
+            if !self.match_rule(&[Token::Comma]) {
+                break;
+            }
+        }
+
+        self.expect_consume(&[end])?;
+
+        Ok((patterns, rest))
+    }
+}
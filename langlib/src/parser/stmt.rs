@@ -1,9 +1,15 @@
 use super::{err::ParserError, Parser};
 use crate::{
-    expr::Expr,
-    func::Func,
-    lexer::token::{Keyword, Token},
-    stmt::{Declaration, Stmt},
+    class::{ClassDecl, TraitDecl},
+    enums::{EnumDecl, VariantDecl},
+    expr::{Expr, Var},
+    func::{Func, Param},
+    lexer::{
+        op::BinOp,
+        token::{Keyword, Token},
+    },
+    pattern::Binding,
+    stmt::{Declaration, ForIn, Stmt},
 };
 
 impl Parser {
@@ -35,24 +41,39 @@ impl Parser {
                 Token::Keyword(keyword) => match keyword {
                     // If it's an Declaration statement
                     Keyword::Let => self.declaration(),
+                    Keyword::Const => self.const_declaration(),
                     Keyword::Print => self.print(),
                     Keyword::If => self.if_stmt(),
                     Keyword::While => self.while_stmt(),
                     Keyword::For => self.for_stmt(),
-                    Keyword::Func => self.func(),
+                    // A `func` followed directly by a `(` is a function expression.
+                    Keyword::Func => match self.at(self.cursor + 1)? {
+                        Token::LeftBracket => Ok(Stmt::Expr(self.expr()?)),
+                        _ => self.func(),
+                    },
                     Keyword::Return => self.return_stmt(),
+                    Keyword::Class => self.class(),
+                    Keyword::Trait => self.trait_decl(),
+                    Keyword::Enum => self.enum_decl(),
+                    Keyword::Match => self.match_stmt(),
+                    Keyword::This | Keyword::Super => self.assignment(),
+                    Keyword::Break => {
+                        let span = self.span_at(self.cursor);
+                        self.adv();
+                        Ok(Stmt::Break(span))
+                    }
+                    Keyword::Continue => {
+                        let span = self.span_at(self.cursor);
+                        self.adv();
+                        Ok(Stmt::Continue(span))
+                    }
 
                     _ => Err(ParserError::BadStatement(self.cursor)),
                 },
 
+                Token::LeftCurly if self.is_map() => Ok(Stmt::Expr(self.expr()?)),
                 Token::LeftCurly => self.block(),
-                Token::Ident(_) => {
-                    if self.at(self.cursor + 1)? != Token::LeftBracket {
-                        self.assignment()
-                    } else {
-                        Ok(Stmt::Expr(self.expr()?))
-                    }
-                }
+                Token::Ident(_) => self.assignment(),
 
                 // Attempt to parse an expression statement
                 _ => match self.expr() {
@@ -68,25 +89,73 @@ impl Parser {
 
     /// Attempts to parse a declaration statement.
     fn declaration(&mut self) -> Result<Stmt, ParserError> {
+        if let Ok(Token::LeftSquare | Token::LeftCurly) = self.at(self.cursor + 1) {
+            return self.destructure();
+        }
+
         self.expect_consume(&[Token::Keyword(Keyword::Let), Token::Ident(String::from(""))])?;
         if self.match_rule(&[Token::DeclarationSign]) {
             // Get the identier and value
             let ident = self.at(self.cursor - 2)?.try_into_ident()?;
+            let span = self.span_at(self.cursor - 2);
 
             let expr = self.expr()?;
 
-            return Ok(Stmt::Declaration(Declaration { ident, val: expr }));
+            return Ok(Stmt::Declaration(Declaration {
+                name: Binding::with_span(&ident, span),
+                ..Declaration::new(&ident, expr)
+            }));
         }
 
         let ident = self.at(self.cursor - 2)?.try_into_ident()?;
+        let span = self.span_at(self.cursor - 2);
 
         // Set the variable to null by default;
         Ok(Stmt::Declaration(Declaration {
-            ident,
-            val: Expr::Null,
+            name: Binding::with_span(&ident, span),
+            ..Declaration::new(&ident, Expr::Null)
+        }))
+    }
+
+    /// Attempts to parse a constant declaration. Unlike `let`, a constant always needs a value.
+    fn const_declaration(&mut self) -> Result<Stmt, ParserError> {
+        if let Ok(Token::LeftSquare | Token::LeftCurly) = self.at(self.cursor + 1) {
+            return self.destructure();
+        }
+
+        self.expect_consume(&[
+            Token::Keyword(Keyword::Const),
+            Token::Ident(String::from("")),
+            Token::DeclarationSign,
+        ])?;
+
+        let ident = self.at(self.cursor - 2)?.try_into_ident()?;
+        let span = self.span_at(self.cursor - 2);
+
+        let expr = self.expr()?;
+
+        Ok(Stmt::Declaration(Declaration {
+            name: Binding::with_span(&ident, span),
+            ..Declaration::new_const(&ident, expr)
         }))
     }
 
+    /// Attempts to parse a declaration that destructures a list or map, with either `let` or `const` (e.g
+    /// `let {name, age = 0} = person;`).
+    fn destructure(&mut self) -> Result<Stmt, ParserError> {
+        let constant = self.match_rule(&[Token::Keyword(Keyword::Const)]);
+
+        if !constant {
+            self.expect_consume(&[Token::Keyword(Keyword::Let)])?;
+        }
+
+        let pattern = self.pattern()?;
+
+        self.expect_consume(&[Token::DeclarationSign])?;
+
+        Ok(Stmt::Destructure(pattern, self.expr()?, constant))
+    }
+
     /// Attempts to parse a print statement.
     fn print(&mut self) -> Result<Stmt, ParserError> {
         if self.match_rule(&[Token::Keyword(Keyword::Print)]) {
@@ -100,7 +169,7 @@ impl Parser {
     }
 
     /// Attempts to parse a block.
-    fn block(&mut self) -> Result<Stmt, ParserError> {
+    pub(super) fn block(&mut self) -> Result<Stmt, ParserError> {
         // Advance from the "{" token.
         self.adv();
 
@@ -166,29 +235,37 @@ impl Parser {
 
         let block = self.block()?;
 
-        Ok(Stmt::While(expr, Box::new(block)))
+        Ok(Stmt::While(expr, Box::new(block), None))
     }
 
-    /// Attempts to parse an assignment.
+    /// Attempts to parse an assignment. The target is parsed like any other expression, so if there's no `=`
+    /// after it this is just an expression statement.
     fn assignment(&mut self) -> Result<Stmt, ParserError> {
-        self.expect_consume(&[Token::Ident(String::from(""))])?;
+        let target = self.expr()?;
 
-        let ident = self.prev()?;
-
-        self.expect_consume(&[Token::DeclarationSign])?;
+        if !self.match_rule(&[Token::DeclarationSign]) {
+            return Ok(Stmt::Expr(target));
+        }
 
         let expr = self.expr()?;
 
-        Ok(Stmt::Assignment(Declaration {
-            ident: ident.try_into_ident()?,
-            val: expr,
-        }))
+        match target {
+            Expr::Var(var) => Ok(Stmt::Assignment(var, expr)),
+            Expr::Index(list, index) => Ok(Stmt::IndexAssignment(*list, *index, expr)),
+            Expr::Get(instance, ident) => Ok(Stmt::SetProperty(*instance, ident, expr)),
+            _ => Err(ParserError::InvalidAssignmentTarget(self.cursor)),
+        }
     }
 
-    /// Attempt to parse a for loop, by parsing it into a while loop.
+    /// Attempt to parse a for loop. C-style loops are parsed into a while loop, while `for (x in iterable)` loops
+    /// get their own statement.
     fn for_stmt(&mut self) -> Result<Stmt, ParserError> {
         self.expect_consume(&[Token::Keyword(Keyword::For), Token::LeftBracket])?;
 
+        if self.is_for_in() {
+            return self.for_in();
+        }
+
         let initializer = self.stmt()?;
 
         let condition = self.expr()?;
@@ -196,61 +273,308 @@ impl Parser {
 
         self.expect_consume(&[Token::RightBracket])?;
 
-        let mut stmts = match self.block()? {
-            Stmt::Block(stmts) => stmts,
-            _ => todo!(),
-        };
-
-        stmts.push(increment);
+        let block = self.block()?;
 
         Ok(Stmt::Block(vec![
             initializer,
-            Stmt::While(condition, Box::new(Stmt::Block(stmts))),
+            Stmt::While(condition, Box::new(block), Some(Box::new(increment))),
         ]))
     }
 
+    /// Checks if the tokens at the cursor are the loop variables of a for-in loop, which is a list of identifiers
+    /// followed by `in`.
+    fn is_for_in(&self) -> bool {
+        let mut i = self.cursor;
+
+        while let Some(Token::Ident(_)) = self.tokens.get(i) {
+            match self.tokens.get(i + 1) {
+                Some(Token::Comma) => i += 2,
+                Some(Token::Op(BinOp::In)) => return true,
+                _ => return false,
+            }
+        }
+
+        false
+    }
+
+    /// Attempts to parse the rest of a for-in loop, after the `(`.
+    fn for_in(&mut self) -> Result<Stmt, ParserError> {
+        let start = self.cursor;
+        let mut idents = Vec::new();
+
+        loop {
+            idents.push(self.curr()?.try_into_ident()?);
+            self.adv();
+
+            if !self.match_rule(&[Token::Comma]) {
+                break;
+            }
+        }
+
+        if idents.len() > 2 {
+            return Err(ParserError::InvalidLoopVars(self.cursor));
+        }
+
+        let span = self.span_from(start);
+
+        self.expect_consume(&[Token::Op(BinOp::In)])?;
+
+        let iterable = self.expr()?;
+
+        self.expect_consume(&[Token::RightBracket])?;
+
+        let body = self.block()?;
+
+        Ok(Stmt::ForIn(ForIn {
+            idents,
+            iterable,
+            body: Box::new(body),
+            span,
+        }))
+    }
+
     pub fn func(&mut self) -> Result<Stmt, ParserError> {
         self.expect_consume(&[Token::Keyword(Keyword::Func)])?;
 
         // Get the identifier and advance.
         let ident = self.curr()?.try_into_ident()?;
+        let span = self.span_at(self.cursor);
         self.adv();
 
-        // Consume the `(`
-        self.expect_consume(&[Token::LeftBracket])?;
+        let func = self.func_body()?;
+
+        // Return the function as a declaration statement
+        Ok(Stmt::Declaration(Declaration {
+            name: Binding::with_span(&ident, span),
+            ..Declaration::new(&ident, Expr::Func(func))
+        }))
+    }
 
-        let mut args = Vec::new();
+    /// Attempts to parse an enum declaration. Variants are separated by commas, and can list the names of the
+    /// values they hold (e.g `enum Shape { Circle(r), Rect(w, h), Empty }`).
+    fn enum_decl(&mut self) -> Result<Stmt, ParserError> {
+        self.expect_consume(&[Token::Keyword(Keyword::Enum)])?;
+
+        let ident = self.curr()?.try_into_ident()?;
+        let span = self.span_at(self.cursor);
+        self.adv();
+
+        self.expect_consume(&[Token::LeftCurly])?;
+
+        let mut variants = Vec::new();
+
+        while self.curr()? != Token::RightCurly {
+            let variant = self.curr()?.try_into_ident()?;
+            self.adv();
+
+            let mut fields = Vec::new();
+
+            if self.match_rule(&[Token::LeftBracket]) {
+                while self.curr()? != Token::RightBracket {
+                    fields.push(self.curr()?.try_into_ident()?);
+                    self.adv();
+
+                    if !self.match_rule(&[Token::Comma]) {
+                        break;
+                    }
+                }
+
+                self.expect_consume(&[Token::RightBracket])?;
+            }
+
+            variants.push(VariantDecl {
+                ident: variant,
+                fields,
+            });
 
-        // Keep parsing the arg identifiers (e.g func f(a, b, c))
-        //                                              ^^^^^^^
-        //                                             this part
-        loop {
             if !self.match_rule(&[Token::Comma]) {
                 break;
             }
-            args.push(self.curr()?.try_into_ident()?);
+        }
+
+        self.expect_consume(&[Token::RightCurly])?;
+
+        Ok(Stmt::Enum(EnumDecl {
+            name: Binding::with_span(&ident, span),
+            ..EnumDecl::new(&ident, variants)
+        }))
+    }
+
+    /// Attempts to parse a class declaration. Methods are written like functions, without the `func` keyword
+    /// (e.g `class Point { init(x) { this.x = x; } }`), and a superclass can be given after a `<`
+    /// (e.g `class Point3 < Point { ... }`). The traits the class implements are listed after `impl`
+    /// (e.g `class Circle impl Shape, Display { ... }`).
+    fn class(&mut self) -> Result<Stmt, ParserError> {
+        self.expect_consume(&[Token::Keyword(Keyword::Class)])?;
+
+        let ident = self.curr()?.try_into_ident()?;
+        let span = self.span_at(self.cursor);
+        self.adv();
+
+        let superclass = if self.match_rule(&[Token::Op(BinOp::LessSign)]) {
+            let superclass = self.curr()?.try_into_ident()?;
+            let superclass = Var::with_span(&superclass, self.span_at(self.cursor));
+            self.adv();
+
+            Some(superclass)
+        } else {
+            None
+        };
+
+        let mut traits = Vec::new();
+
+        if self.match_rule(&[Token::Keyword(Keyword::Impl)]) {
+            loop {
+                traits.push(self.curr()?.try_into_ident()?);
+                self.adv();
+
+                if !self.match_rule(&[Token::Comma]) {
+                    break;
+                }
+            }
+        }
 
+        self.expect_consume(&[Token::LeftCurly])?;
+
+        let mut methods = Vec::new();
+
+        while self.curr()? != Token::RightCurly {
+            let method = self.curr()?.try_into_ident()?;
             self.adv();
+
+            methods.push((method, self.func_body()?));
         }
 
-        // Consume the `)`
-        self.expect_consume(&[Token::RightBracket])?;
+        self.expect_consume(&[Token::RightCurly])?;
+
+        Ok(Stmt::Class(ClassDecl {
+            name: Binding::with_span(&ident, span),
+            ..ClassDecl::new(&ident, superclass, traits, methods)
+        }))
+    }
+
+    /// Attempts to parse a trait declaration, which lists the methods a class needs and their parameters
+    /// (e.g `trait Shape { area(); scale(factor); }`).
+    fn trait_decl(&mut self) -> Result<Stmt, ParserError> {
+        self.expect_consume(&[Token::Keyword(Keyword::Trait)])?;
+
+        let ident = self.curr()?.try_into_ident()?;
+        let span = self.span_at(self.cursor);
+        self.adv();
+
+        self.expect_consume(&[Token::LeftCurly])?;
+
+        let mut methods = Vec::new();
+
+        while self.curr()? != Token::RightCurly {
+            let method = self.curr()?.try_into_ident()?;
+            self.adv();
+
+            self.expect_consume(&[Token::LeftBracket])?;
+
+            // Only the number of parameters matters, their names are just documentation
+            let mut arity = 0;
+
+            while self.curr()? != Token::RightBracket {
+                self.curr()?.try_into_ident()?;
+                self.adv();
+                arity += 1;
+
+                if !self.match_rule(&[Token::Comma]) {
+                    break;
+                }
+            }
+
+            self.expect_consume(&[Token::RightBracket])?;
+
+            methods.push((method, arity));
+        }
+
+        self.expect_consume(&[Token::RightCurly])?;
+
+        Ok(Stmt::Trait(TraitDecl {
+            span,
+            ..TraitDecl::new(&ident, methods)
+        }))
+    }
+
+    /// Attempts to parse the parameters and body of a function (e.g `(a, b) { ... }`).
+    pub(super) fn func_body(&mut self) -> Result<Func, ParserError> {
+        let (args, rest) = self.params()?;
 
         // Parse the body
         let body = self.block()?;
 
-        // Return the function as a declaration statement
-        Ok(Stmt::Declaration(Declaration {
-            ident,
-            val: Expr::Func(Func::new(body, args)),
-        }))
+        Ok(Func::new(body, args, rest))
+    }
+
+    /// Attempts to parse a parameter list, including the brackets. Parameters may have default values
+    /// (e.g `b = 2`), and the last one may be a rest parameter (e.g `...rest`). A trailing comma is allowed.
+    pub(super) fn params(&mut self) -> Result<(Vec<Param>, Option<String>), ParserError> {
+        // Consume the `(`
+        self.expect_consume(&[Token::LeftBracket])?;
+
+        let mut args: Vec<Param> = Vec::new();
+        let mut rest = None;
+
+        // Keep parsing the arg identifiers (e.g func f(a, b = 2, ...c))
+        //                                              ^^^^^^^^^^^^^^
+        //                                                this part
+        while self.curr()? != Token::RightBracket {
+            // Nothing is allowed to come after the rest parameter
+            if rest.is_some() {
+                return Err(ParserError::RestParamNotLast(self.cursor));
+            }
+
+            if self.match_rule(&[Token::Ellipsis]) {
+                rest = Some(self.curr()?.try_into_ident()?);
+                self.adv();
+            } else {
+                let ident = self.curr()?.try_into_ident()?;
+                let span = self.span_at(self.cursor);
+                self.adv();
+
+                let default = if self.match_rule(&[Token::DeclarationSign]) {
+                    Some(self.expr()?)
+                } else {
+                    None
+                };
+
+                // A required parameter can't follow one with a default value
+                if default.is_none() && args.last().is_some_and(|arg| arg.default.is_some()) {
+                    return Err(ParserError::RequiredParamAfterDefault(ident, self.cursor));
+                }
+
+                args.push(Param {
+                    ident,
+                    default,
+                    span,
+                });
+            }
+
+            if !self.match_rule(&[Token::Comma]) {
+                break;
+            }
+        }
+
+        // Consume the `)`
+        self.expect_consume(&[Token::RightBracket])?;
+
+        Ok((args, rest))
     }
 
     pub fn return_stmt(&mut self) -> Result<Stmt, ParserError> {
+        let span = self.span_at(self.cursor);
         self.expect_consume(&[Token::Keyword(Keyword::Return)])?;
 
+        // A bare `return;` returns null. The semicolon has already been skipped at this point, so check for it
+        // behind the cursor.
+        if self.prev()? == Token::Semi || self.is_at_end() || self.curr()? == Token::RightCurly {
+            return Ok(Stmt::Return(Expr::Null, span));
+        }
+
         let expr = self.expr()?;
 
-        Ok(Stmt::Return(expr))
+        Ok(Stmt::Return(expr, span))
     }
 }
@@ -3,14 +3,19 @@
 mod parser_tests {
 
     use crate::{
-        expr::{BinExpr, Expr},
+        class::{ClassDecl, TraitDecl},
+        enums::{EnumDecl, VariantDecl},
+        expr::{BinExpr, Expr, Var},
+        func::{Func, Param},
         lexer::{
             op::{BinOp, UnOp},
             token::{Keyword, Token},
             Lexer,
         },
-        parser::Parser,
-        stmt::{Declaration, Stmt},
+        parser::{err::ParserError, Parser},
+        pattern::{Arm, Binding, Match, Pattern},
+        stmt::{Declaration, ForIn, Stmt},
+        value::Value,
     };
 
     #[test]
@@ -119,7 +124,7 @@ mod parser_tests {
         assert!(result.is_ok());
 
         let result = result.unwrap();
-        assert_eq!(result, Expr::Bool(false));
+        assert_eq!(result, Value::Bool(false));
     }
 
     #[test]
@@ -138,7 +143,7 @@ mod parser_tests {
 
         let result = result.unwrap();
 
-        assert_eq!(result, Expr::Bool(true));
+        assert_eq!(result, Value::Bool(true));
     }
 
     #[test]
@@ -159,7 +164,7 @@ mod parser_tests {
         assert!(result.is_ok());
 
         let result = result.unwrap();
-        assert_eq!(result, Expr::Bool(false));
+        assert_eq!(result, Value::Bool(false));
     }
 
     #[test]
@@ -178,7 +183,7 @@ mod parser_tests {
 
         let result = result.unwrap();
 
-        assert_eq!(result, Expr::Bool(true));
+        assert_eq!(result, Value::Bool(true));
     }
 
     #[test]
@@ -197,7 +202,7 @@ mod parser_tests {
 
         let result = result.unwrap();
 
-        assert_eq!(result, Expr::Bool(false));
+        assert_eq!(result, Value::Bool(false));
     }
 
     #[test]
@@ -215,14 +220,17 @@ mod parser_tests {
         assert_eq!(
             binding_stmt,
             Stmt::Declaration(Declaration {
-                ident: "x".to_owned(),
+                name: Binding::new("x"),
+                constant: false,
                 val: Expr::Unary(
                     UnOp::Bang,
                     Box::new(Expr::Bin(BinExpr {
                         lhs: Box::new(Expr::Bool(true)),
                         rhs: Box::new(Expr::Bool(false)),
-                        op: BinOp::EqSign
-                    }))
+                        op: BinOp::EqSign,
+                        span: None
+                    })),
+                    None
                 )
             })
         );
@@ -243,14 +251,17 @@ mod parser_tests {
         assert_eq!(
             binding_stmt,
             Stmt::Declaration(Declaration {
-                ident: "x".to_owned(),
+                name: Binding::new("x"),
+                constant: false,
                 val: Expr::Unary(
                     UnOp::Bang,
                     Box::new(Expr::Bin(BinExpr {
                         lhs: Box::new(Expr::Str("this is a string.".to_owned())),
                         rhs: Box::new(Expr::Str("this is another string.".to_owned())),
-                        op: BinOp::EqSign
-                    }))
+                        op: BinOp::EqSign,
+                        span: None
+                    })),
+                    None
                 )
             })
         );
@@ -283,44 +294,54 @@ mod parser_tests {
             statements,
             vec![
                 Stmt::Declaration(Declaration {
-                    ident: "x".to_owned(),
+                    name: Binding::new("x"),
+                    constant: false,
                     val: Expr::Unary(
                         UnOp::Bang,
                         Box::new(Expr::Bin(BinExpr {
                             lhs: Box::new(Expr::Str("this is a string.".to_owned())),
                             rhs: Box::new(Expr::Str("this is another string.".to_owned())),
-                            op: BinOp::EqSign
-                        }))
+                            op: BinOp::EqSign,
+                            span: None
+                        })),
+                        None
                     )
                 }),
                 Stmt::Print(Expr::Bin(BinExpr {
                     lhs: Box::new(Expr::Bin(BinExpr {
                         lhs: Box::new(Expr::Num(23)),
                         rhs: Box::new(Expr::Num(5)),
-                        op: BinOp::Sub
+                        op: BinOp::Sub,
+                        span: None
                     })),
                     rhs: Box::new(Expr::Num(2)),
-                    op: BinOp::Div
+                    op: BinOp::Div,
+                    span: None
                 })),
                 Stmt::Declaration(Declaration {
-                    ident: "y".to_owned(),
+                    name: Binding::new("y"),
+                    constant: false,
                     val: Expr::Bin(BinExpr {
                         lhs: Box::new(Expr::Bin(BinExpr {
                             lhs: Box::new(Expr::Num(2)),
                             rhs: Box::new(Expr::Num(4)),
-                            op: BinOp::Add
+                            op: BinOp::Add,
+                            span: None
                         })),
                         rhs: Box::new(Expr::Num(2)),
-                        op: BinOp::Div
+                        op: BinOp::Div,
+                        span: None
                     })
                 }),
                 Stmt::Declaration(Declaration {
-                    ident: "z".to_owned(),
-                    val: Expr::Unary(UnOp::Bang, Box::new(Expr::Bool(true)))
+                    name: Binding::new("z"),
+                    constant: false,
+                    val: Expr::Unary(UnOp::Bang, Box::new(Expr::Bool(true)), None)
                 }),
                 Stmt::Print(Expr::Str("This is a very cool string.".to_owned())),
                 Stmt::Declaration(Declaration {
-                    ident: "undefinedVar".to_owned(),
+                    name: Binding::new("undefinedVar"),
+                    constant: false,
                     val: Expr::Null
                 })
             ]
@@ -344,7 +365,8 @@ mod parser_tests {
                 Expr::Bin(BinExpr {
                     lhs: Box::new(Expr::Num(2)),
                     rhs: Box::new(Expr::Num(5)),
-                    op: BinOp::EqSign
+                    op: BinOp::EqSign,
+                    span: None
                 }),
                 Box::new(Stmt::Block(vec![
                     Stmt::Print(Expr::Str("2 is equal to 5".to_owned())),
@@ -375,7 +397,8 @@ mod parser_tests {
                 Expr::Bin(BinExpr {
                     lhs: Box::new(Expr::Num(2)),
                     rhs: Box::new(Expr::Num(5)),
-                    op: BinOp::EqSign
+                    op: BinOp::EqSign,
+                    span: None
                 }),
                 Box::new(Stmt::Block(vec![
                     Stmt::Print(Expr::Str("2 is equal to 5".to_owned())),
@@ -403,7 +426,8 @@ mod parser_tests {
                 Expr::Bin(BinExpr {
                     lhs: Box::new(Expr::Num(2)),
                     rhs: Box::new(Expr::Num(5)),
-                    op: BinOp::EqSign
+                    op: BinOp::EqSign,
+                    span: None
                 }),
                 Box::new(Stmt::Print(Expr::Str(
                     "We just broke the laws of the universe.".to_owned()
@@ -489,21 +513,46 @@ mod parser_tests {
                 Expr::Bin(BinExpr {
                     lhs: Box::new(Expr::Num(5)),
                     rhs: Box::new(Expr::Num(5)),
-                    op: BinOp::EqSign
+                    op: BinOp::EqSign,
+                    span: None
                 }),
                 Box::new(Stmt::Block(vec![Stmt::If(
                     Expr::Bin(BinExpr {
                         lhs: Box::new(Expr::Bool(true)),
                         rhs: Box::new(Expr::Bool(false)),
-                        op: BinOp::Or
+                        op: BinOp::Or,
+                        span: None
                     }),
                     Box::new(Stmt::Block(vec![Stmt::Print(Expr::Str("HERE".to_owned()))])),
                     None
-                )]))
+                )])),
+                None
             )]
         );
     }
 
+    #[test]
+    fn test_bare_return() {
+        let s = "func f() { return; } func g() { return; }";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let ast = Parser::new(tokens).get_statements().unwrap();
+
+        let bare_return = |ident| {
+            Stmt::Declaration(Declaration::new(
+                ident,
+                Expr::Func(Func::new(
+                    Stmt::Block(vec![Stmt::Return(Expr::Null, None)]),
+                    vec![],
+                    None,
+                )),
+            ))
+        };
+
+        assert_eq!(ast, [bare_return("f"), bare_return("g")]);
+    }
+
     #[test]
     fn test_simple_function_call() {
         let s = "someFunction();";
@@ -512,7 +561,11 @@ mod parser_tests {
 
         assert_eq!(
             ast.unwrap(),
-            Expr::Funcall(Box::new(Expr::Var("someFunction".to_owned())), vec![])
+            Expr::Funcall(
+                Box::new(Expr::Var(Var::new("someFunction"))),
+                vec![],
+                vec![]
+            )
         );
     }
 
@@ -525,25 +578,29 @@ mod parser_tests {
         assert_eq!(
             ast.unwrap(),
             Expr::Funcall(
-                Box::new(Expr::Var("someFunction".to_owned())),
+                Box::new(Expr::Var(Var::new("someFunction"))),
                 vec![
                     Expr::Bin(BinExpr {
                         lhs: Box::new(Expr::Bin(BinExpr {
                             lhs: Box::new(Expr::Num(123456789)),
                             rhs: Box::new(Expr::Num(2)),
-                            op: BinOp::Mul
+                            op: BinOp::Mul,
+                            span: None
                         })),
                         rhs: Box::new(Expr::Num(3)),
-                        op: BinOp::Div
+                        op: BinOp::Div,
+                        span: None
                     }),
                     Expr::Str("A very cool string".to_owned()),
-                    Expr::Unary(UnOp::Bang, Box::new(Expr::Bool(true))),
+                    Expr::Unary(UnOp::Bang, Box::new(Expr::Bool(true)), None),
                     Expr::Bin(BinExpr {
-                        lhs: Box::new(Expr::Var("a".to_owned())),
+                        lhs: Box::new(Expr::Var(Var::new("a"))),
                         rhs: Box::new(Expr::Num(2)),
-                        op: BinOp::Mul
+                        op: BinOp::Mul,
+                        span: None
                     })
-                ]
+                ],
+                vec![]
             )
         );
     }
@@ -561,37 +618,679 @@ mod parser_tests {
             Expr::Funcall(
                 Box::new(Expr::Funcall(
                     Box::new(Expr::Funcall(
-                        Box::new(Expr::Var("someFunction".to_string())),
+                        Box::new(Expr::Var(Var::new("someFunction"))),
                         vec![
                             Expr::Bin(BinExpr {
                                 lhs: Box::new(Expr::Bin(BinExpr {
                                     lhs: Box::new(Expr::Num(123456789)),
                                     rhs: Box::new(Expr::Num(2)),
-                                    op: BinOp::Mul
+                                    op: BinOp::Mul,
+                                    span: None
                                 })),
                                 rhs: Box::new(Expr::Num(3)),
-                                op: BinOp::Div
+                                op: BinOp::Div,
+                                span: None
                             }),
                             Expr::Str("A very cool string".to_owned()),
-                            Expr::Unary(UnOp::Bang, Box::new(Expr::Bool(true))),
+                            Expr::Unary(UnOp::Bang, Box::new(Expr::Bool(true)), None),
                             Expr::Bin(BinExpr {
-                                lhs: Box::new(Expr::Var("a".to_string())),
+                                lhs: Box::new(Expr::Var(Var::new("a"))),
                                 rhs: Box::new(Expr::Num(2)),
-                                op: BinOp::Mul
+                                op: BinOp::Mul,
+                                span: None
                             })
-                        ]
+                        ],
+                        vec![]
                     )),
-                    vec![Expr::Num(32), Expr::Num(65), Expr::Num(21)]
+                    vec![Expr::Num(32), Expr::Num(65), Expr::Num(21)],
+                    vec![]
                 )),
                 vec![
-                    Expr::Var("a".to_owned()),
-                    Expr::Var("b".to_owned()),
-                    Expr::Var("c".to_owned())
+                    Expr::Var(Var::new("a")),
+                    Expr::Var(Var::new("b")),
+                    Expr::Var(Var::new("c"))
+                ],
+                vec![]
+            )
+        );
+    }
+
+    #[test]
+    fn test_named_args() {
+        let s = "connect(\"localhost\", retries: 3, verbose: true);";
+        let tokens = Lexer::new(s).tokenize().unwrap();
+        let expr = Parser::new(tokens).expr();
+
+        assert_eq!(
+            expr.unwrap(),
+            Expr::Funcall(
+                Box::new(Expr::Var(Var::new("connect"))),
+                vec![Expr::Str("localhost".to_owned())],
+                vec![
+                    ("retries".to_owned(), Expr::Num(3)),
+                    ("verbose".to_owned(), Expr::Bool(true))
                 ]
             )
         );
     }
+
+    #[test]
+    fn test_positional_after_named_arg() {
+        let s = "connect(retries: 3, \"localhost\");";
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert!(matches!(
+            Parser::new(tokens).expr(),
+            Err(ParserError::PositionalAfterNamedArg(_))
+        ));
+    }
+
+    #[test]
+    fn test_func_expression() {
+        let s = "let add = func (a, b) { return a + b; };";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let ast = Parser::new(tokens).get_statements();
+
+        assert_eq!(
+            ast.unwrap(),
+            [Stmt::Declaration(Declaration {
+                name: Binding::new("add"),
+                constant: false,
+                val: Expr::Func(Func::new(
+                    Stmt::Block(vec![Stmt::Return(
+                        Expr::Bin(BinExpr {
+                            lhs: Box::new(Expr::Var(Var::new("a"))),
+                            rhs: Box::new(Expr::Var(Var::new("b"))),
+                            op: BinOp::Add,
+                            span: None
+                        }),
+                        None
+                    )]),
+                    vec![Param::new("a", None), Param::new("b", None)],
+                    None
+                ))
+            })]
+        );
+    }
+
+    #[test]
+    fn test_arrow_func() {
+        let s = "apply((a, b) => a * b, () => { print 1; });";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let expr = Parser::new(tokens).expr();
+
+        assert_eq!(
+            expr.unwrap(),
+            Expr::Funcall(
+                Box::new(Expr::Var(Var::new("apply"))),
+                vec![
+                    Expr::Func(Func::new(
+                        Stmt::Block(vec![Stmt::Return(
+                            Expr::Bin(BinExpr {
+                                lhs: Box::new(Expr::Var(Var::new("a"))),
+                                rhs: Box::new(Expr::Var(Var::new("b"))),
+                                op: BinOp::Mul,
+                                span: None
+                            }),
+                            None
+                        )]),
+                        vec![Param::new("a", None), Param::new("b", None)],
+                        None
+                    )),
+                    Expr::Func(Func::new(
+                        Stmt::Block(vec![Stmt::Print(Expr::Num(1))]),
+                        vec![],
+                        None
+                    ))
+                ],
+                vec![]
+            )
+        );
+    }
+
+    #[test]
+    fn test_func_params() {
+        let s = "func sayHi(first, last = \"Smith\", ...rest,) { print first; }";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let ast = Parser::new(tokens).stmt();
+
+        assert_eq!(
+            ast.unwrap(),
+            Stmt::Declaration(Declaration {
+                name: Binding::new("sayHi"),
+                constant: false,
+                val: Expr::Func(Func::new(
+                    Stmt::Block(vec![Stmt::Print(Expr::Var(Var::new("first")))]),
+                    vec![
+                        Param::new("first", None),
+                        Param::new("last", Some(Expr::Str("Smith".to_owned())))
+                    ],
+                    Some("rest".to_owned())
+                ))
+            })
+        );
+    }
+
+    #[test]
+    fn test_bad_func_params() {
+        let rest_not_last = "func f(...rest, a) { print a; }";
+        let tokens = Lexer::new(rest_not_last).tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).stmt(),
+            Err(ParserError::RestParamNotLast(_))
+        ));
+
+        let required_after_default = "func f(a = 1, b) { print a; }";
+        let tokens = Lexer::new(required_after_default).tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).stmt(),
+            Err(ParserError::RequiredParamAfterDefault(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_deeply_nested_brackets() {
+        // Every group has to find the bracket that closes it before it can tell if it's an arrow function
+        let s = format!("{}1{}", "(".repeat(50), ")".repeat(50));
+
+        let tokens = Lexer::new(&s).tokenize().unwrap();
+
+        assert_eq!(Parser::new(tokens).expr().unwrap(), Expr::Num(1));
+
+        let tokens = Lexer::new("((a) => a)(1)").tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).expr().unwrap(),
+            Expr::Funcall(callee, ..) if matches!(*callee, Expr::Func(_))
+        ));
+    }
+
+    #[test]
+    fn test_bracketed_expr_is_not_arrow_func() {
+        let s = "(a + b) * (c)";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let expr = Parser::new(tokens).expr();
+
+        assert!(matches!(expr.unwrap(), Expr::Bin(_)));
+    }
+
+    #[test]
+    fn test_lists() {
+        let s = "let xs = [1, [2], xs[-1], xs[1:], xs[:],];";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let xs = || Box::new(Expr::Var(Var::new("xs")));
+
+        assert_eq!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::Declaration(Declaration::new(
+                "xs",
+                Expr::List(vec![
+                    Expr::Num(1),
+                    Expr::List(vec![Expr::Num(2)]),
+                    Expr::Index(
+                        xs(),
+                        Box::new(Expr::Unary(UnOp::Minus, Box::new(Expr::Num(1)), None))
+                    ),
+                    Expr::Slice(xs(), Some(Box::new(Expr::Num(1))), None),
+                    Expr::Slice(xs(), None, None),
+                ])
+            ))
+        );
+
+        let tokens = Lexer::new("xs[];").tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).stmt(),
+            Err(ParserError::ExpectedExpr(_))
+        ));
+    }
+
+    #[test]
+    fn test_index_assignment() {
+        let s = "xs[0][1] = 2;";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::IndexAssignment(
+                Expr::Index(Box::new(Expr::Var(Var::new("xs"))), Box::new(Expr::Num(0))),
+                Expr::Num(1),
+                Expr::Num(2)
+            )
+        );
+
+        let tokens = Lexer::new("f() = 2;").tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).stmt(),
+            Err(ParserError::InvalidAssignmentTarget(_))
+        ));
+    }
+
+    #[test]
+    fn test_map_literal() {
+        let s = "let m = {\"a\": 1, 2: {},};";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::Declaration(Declaration::new(
+                "m",
+                Expr::Map(vec![
+                    (Expr::Str("a".to_owned()), Expr::Num(1)),
+                    (Expr::Num(2), Expr::Map(vec![])),
+                ])
+            ))
+        );
+    }
+
+    #[test]
+    fn test_map_or_block() {
+        let tokens = Lexer::new("{ \"a\": 1 };").tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::Expr(Expr::Map(_))
+        ));
+
+        let tokens = Lexer::new("{ print a; }").tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::Block(_)
+        ));
+    }
+
+    #[test]
+    fn test_for_in() {
+        let s = "for (k, v in 0..=n + 1) { print k; }";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::ForIn(ForIn {
+                idents: vec!["k".to_owned(), "v".to_owned()],
+                iterable: Expr::Range(
+                    Box::new(Expr::Num(0)),
+                    Box::new(Expr::Bin(BinExpr::new(
+                        Box::new(Expr::Var(Var::new("n"))),
+                        Box::new(Expr::Num(1)),
+                        BinOp::Add
+                    ))),
+                    true
+                ),
+                body: Box::new(Stmt::Block(vec![Stmt::Print(Expr::Var(Var::new("k")))])),
+                span: None
+            })
+        );
+
+        // A C-style loop that starts with an assignment is still parsed as one
+        let s = "for (i = 0; i < 3; i = i + 1) { print i; }";
+        let tokens = Lexer::new(s).tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::Block(_)
+        ));
+    }
+
+    #[test]
+    fn test_class() {
+        let s = "class Point { init(x) { this.x = x; } norm() { return this.x; } }";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let this_x = Expr::Get(Box::new(Expr::Var(Var::new("this"))), "x".to_owned());
+
+        assert_eq!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::Class(ClassDecl::new(
+                "Point",
+                None,
+                vec![],
+                vec![
+                    (
+                        "init".to_owned(),
+                        Func::new(
+                            Stmt::Block(vec![Stmt::SetProperty(
+                                Expr::Var(Var::new("this")),
+                                "x".to_owned(),
+                                Expr::Var(Var::new("x"))
+                            )]),
+                            vec![Param::new("x", None)],
+                            None
+                        )
+                    ),
+                    (
+                        "norm".to_owned(),
+                        Func::new(Stmt::Block(vec![Stmt::Return(this_x, None)]), vec![], None)
+                    )
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_traits() {
+        let s = "trait Shape { area(); scale(x, y,); } class Square < Rect impl Shape, Show {};";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            Parser::new(tokens).get_statements().unwrap(),
+            [
+                Stmt::Trait(TraitDecl::new(
+                    "Shape",
+                    vec![("area".to_owned(), 0), ("scale".to_owned(), 2)]
+                )),
+                Stmt::Class(ClassDecl::new(
+                    "Square",
+                    Some(Var::new("Rect")),
+                    vec!["Shape".to_owned(), "Show".to_owned()],
+                    vec![]
+                ))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enum() {
+        let s = "enum Shape { Circle(r), Rect(w, h,), Empty, };";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::Enum(EnumDecl::new(
+                "Shape",
+                vec![
+                    VariantDecl::new("Circle", &["r"]),
+                    VariantDecl::new("Rect", &["w", "h"]),
+                    VariantDecl::new("Empty", &[])
+                ]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_match() {
+        let s = "let a = match (v) { -1 => 0, [x, ...rest] if x => x, {\"k\": _} => 1, E.A(y) => y, E.B => 2 };";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        let arm = |pattern, guard, body| Arm {
+            pattern,
+            guard,
+            body,
+        };
+
+        assert_eq!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::Declaration(Declaration::new(
+                "a",
+                Expr::Match(Box::new(Match {
+                    val: Expr::Var(Var::new("v")),
+                    arms: vec![
+                        arm(
+                            Pattern::Literal(Expr::Unary(
+                                UnOp::Minus,
+                                Box::new(Expr::Num(1)),
+                                None
+                            )),
+                            None,
+                            Expr::Num(0)
+                        ),
+                        arm(
+                            Pattern::List {
+                                items: vec![Pattern::Binding(Binding::new("x"))],
+                                rest: Some(Box::new(Pattern::Binding(Binding::new("rest"))))
+                            },
+                            Some(Expr::Var(Var::new("x"))),
+                            Expr::Var(Var::new("x"))
+                        ),
+                        arm(
+                            Pattern::Map(vec![(Expr::Str("k".to_owned()), Pattern::Wildcard)]),
+                            None,
+                            Expr::Num(1)
+                        ),
+                        arm(
+                            Pattern::Variant {
+                                enum_var: Var::new("E"),
+                                variant: "A".to_owned(),
+                                fields: Some(vec![Pattern::Binding(Binding::new("y"))])
+                            },
+                            None,
+                            Expr::Var(Var::new("y"))
+                        ),
+                        arm(
+                            Pattern::Variant {
+                                enum_var: Var::new("E"),
+                                variant: "B".to_owned(),
+                                fields: None
+                            },
+                            None,
+                            Expr::Num(2)
+                        )
+                    ]
+                }))
+            ))
+        );
+
+        // Statement arms can be blocks, which don't need commas
+        let s = "match (v) { 1 => { print 1; } _ => f(2) }";
+        let tokens = Lexer::new(s).tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::Match(parsed) if parsed.arms.len() == 2
+        ));
+
+        let tokens = Lexer::new("match (v) { [...a, b] => 1 };")
+            .tokenize()
+            .unwrap();
+        assert!(matches!(
+            Parser::new(tokens).stmt(),
+            Err(ParserError::RestPatternNotLast(_))
+        ));
+    }
+
+    #[test]
+    fn test_optional_chaining() {
+        let tokens = Lexer::new("a?.b.c?.(1)").tokenize().unwrap();
+
+        let a = Box::new(Expr::Var(Var::new("a")));
+
+        // The optional links and everything after them are part of one chain
+        assert_eq!(
+            Parser::new(tokens).expr().unwrap(),
+            Expr::Chain(Box::new(Expr::OptionalCall(
+                Box::new(Expr::Get(
+                    Box::new(Expr::OptionalGet(a, "b".to_owned())),
+                    "c".to_owned()
+                )),
+                vec![Expr::Num(1)],
+                Vec::new()
+            )))
+        );
+
+        let tokens = Lexer::new("a ?? b or c").tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).expr().unwrap(),
+            Expr::Bin(BinExpr { op: BinOp::Coalesce, rhs, .. })
+                if matches!(*rhs, Expr::Bin(BinExpr { op: BinOp::Or, .. }))
+        ));
+
+        let tokens = Lexer::new("let a = null;").tokenize().unwrap();
+        assert_eq!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::Declaration(Declaration::new("a", Expr::Null))
+        );
+
+        let tokens = Lexer::new("a?.b = 1;").tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).stmt(),
+            Err(ParserError::InvalidAssignmentTarget(_))
+        ));
+    }
+
+    #[test]
+    fn test_ternary() {
+        let tokens = Lexer::new("a ? b : c ? d : e").tokenize().unwrap();
+
+        let var = |ident| Box::new(Expr::Var(Var::new(ident)));
+
+        // Conditionals nest to the right
+        assert_eq!(
+            Parser::new(tokens).expr().unwrap(),
+            Expr::Ternary(
+                var("a"),
+                var("b"),
+                Box::new(Expr::Ternary(var("c"), var("d"), var("e")))
+            )
+        );
+
+        // and bind looser than any operator
+        let tokens = Lexer::new("x or y ? 1 + 2 : 3").tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).expr().unwrap(),
+            Expr::Ternary(condition, then_branch, _)
+                if matches!(*condition, Expr::Bin(BinExpr { op: BinOp::Or, .. }))
+                    && matches!(*then_branch, Expr::Bin(BinExpr { op: BinOp::Add, .. }))
+        ));
+
+        let tokens = Lexer::new("a ? b;").tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).expr(),
+            Err(ParserError::FailedRuleMatch(..))
+        ));
+    }
+
+    #[test]
+    fn test_const() {
+        let tokens = Lexer::new("const limit = 10;").tokenize().unwrap();
+
+        assert_eq!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::Declaration(Declaration::new_const("limit", Expr::Num(10)))
+        );
+
+        // Constants always need a value
+        let tokens = Lexer::new("const limit;").tokenize().unwrap();
+        assert!(matches!(
+            Parser::new(tokens).stmt(),
+            Err(ParserError::FailedRuleMatch(..))
+        ));
+    }
+
+    #[test]
+    fn test_destructure() {
+        let s = "let [a, [b], c = 1, ...rest] = xs;";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::Destructure(
+                Pattern::List {
+                    items: vec![
+                        Pattern::Binding(Binding::new("a")),
+                        Pattern::List {
+                            items: vec![Pattern::Binding(Binding::new("b"))],
+                            rest: None
+                        },
+                        Pattern::Default(
+                            Box::new(Pattern::Binding(Binding::new("c"))),
+                            Expr::Num(1)
+                        ),
+                    ],
+                    rest: Some(Box::new(Pattern::Binding(Binding::new("rest"))))
+                },
+                Expr::Var(Var::new("xs")),
+                false
+            )
+        );
+
+        let s = "let {name, age = 0, \"pos\": [x, _]} = person;";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::Destructure(
+                Pattern::Map(vec![
+                    (
+                        Expr::Str("name".to_owned()),
+                        Pattern::Binding(Binding::new("name"))
+                    ),
+                    (
+                        Expr::Str("age".to_owned()),
+                        Pattern::Default(
+                            Box::new(Pattern::Binding(Binding::new("age"))),
+                            Expr::Num(0)
+                        )
+                    ),
+                    (
+                        Expr::Str("pos".to_owned()),
+                        Pattern::List {
+                            items: vec![Pattern::Binding(Binding::new("x")), Pattern::Wildcard],
+                            rest: None
+                        }
+                    ),
+                ]),
+                Expr::Var(Var::new("person")),
+                false
+            )
+        );
+
+        // Patterns work with `const` as well
+        let tokens = Lexer::new("const {x} = m;").tokenize().unwrap();
+
+        assert_eq!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::Destructure(
+                Pattern::Map(vec![(
+                    Expr::Str("x".to_owned()),
+                    Pattern::Binding(Binding::new("x"))
+                )]),
+                Expr::Var(Var::new("m")),
+                true
+            )
+        );
+    }
+
+    #[test]
+    fn test_property_access() {
+        let s = "a.b(1).c = d.e;";
+
+        let tokens = Lexer::new(s).tokenize().unwrap();
+
+        assert_eq!(
+            Parser::new(tokens).stmt().unwrap(),
+            Stmt::SetProperty(
+                Expr::Funcall(
+                    Box::new(Expr::Get(
+                        Box::new(Expr::Var(Var::new("a"))),
+                        "b".to_owned()
+                    )),
+                    vec![Expr::Num(1)],
+                    vec![]
+                ),
+                "c".to_owned(),
+                Expr::Get(Box::new(Expr::Var(Var::new("d"))), "e".to_owned())
+            )
+        );
+
+        let tokens = Lexer::new("a.1;").tokenize().unwrap();
+        assert!(Parser::new(tokens).stmt().is_err());
+    }
 }
+
 #[cfg(test)]
 mod expr_tests {
     use crate::{
@@ -656,7 +1355,7 @@ mod expr_tests {
         assert!(expr.is_ok());
         assert_eq!(
             expr.unwrap(),
-            Expr::Unary(UnOp::Bang, Box::new(Expr::Bool(true)))
+            Expr::Unary(UnOp::Bang, Box::new(Expr::Bool(true)), None)
         );
 
         // Test for the expression !"Hello!", despite the fact that this makes no sense in practice.
@@ -679,7 +1378,7 @@ mod expr_tests {
         assert!(expr.is_ok());
         assert_eq!(
             expr.unwrap(),
-            Expr::Unary(UnOp::Bang, Box::new(Expr::Num(1234)))
+            Expr::Unary(UnOp::Bang, Box::new(Expr::Num(1234)), None)
         );
     }
 
@@ -693,9 +1392,10 @@ mod expr_tests {
         assert_eq!(
             expr.unwrap(),
             Expr::Bin(BinExpr {
-                lhs: Box::new(Expr::Unary(UnOp::Bang, Box::new(Expr::Bool(true)))),
+                lhs: Box::new(Expr::Unary(UnOp::Bang, Box::new(Expr::Bool(true)), None)),
                 rhs: Box::new(Expr::Bool(false)),
-                op: BinOp::Mul
+                op: BinOp::Mul,
+                span: None
             })
         );
 
@@ -708,9 +1408,10 @@ mod expr_tests {
         assert_eq!(
             expr.unwrap(),
             Expr::Bin(BinExpr {
-                lhs: Box::new(Expr::Unary(UnOp::Bang, Box::new(Expr::Bool(true)))),
+                lhs: Box::new(Expr::Unary(UnOp::Bang, Box::new(Expr::Bool(true)), None)),
                 rhs: Box::new(Expr::Str("Some string".to_owned())),
-                op: BinOp::Div
+                op: BinOp::Div,
+                span: None
             })
         );
 
@@ -741,10 +1442,12 @@ mod expr_tests {
                 lhs: Box::new(Expr::Bin(BinExpr {
                     lhs: Box::new(Expr::Num(12)),
                     rhs: Box::new(Expr::Num(43)),
-                    op: BinOp::GreaterSign
+                    op: BinOp::GreaterSign,
+                    span: None
                 })),
                 rhs: Box::new(Expr::Num(324)),
-                op: BinOp::LessEqSign
+                op: BinOp::LessEqSign,
+                span: None
             })
         );
     }
@@ -766,30 +1469,38 @@ mod expr_tests {
                             rhs: Box::new(Expr::Bin(BinExpr {
                                 lhs: Box::new(Expr::Num(324)),
                                 rhs: Box::new(Expr::Num(23)),
-                                op: BinOp::Div
+                                op: BinOp::Div,
+                                span: None
                             })),
-                            op: BinOp::Add
+                            op: BinOp::Add,
+                            span: None
                         })),
                         rhs: Box::new(Expr::Bin(BinExpr {
                             lhs: Box::new(Expr::Num(25)),
                             rhs: Box::new(Expr::Num(234)),
-                            op: BinOp::Div
+                            op: BinOp::Div,
+                            span: None
                         })),
-                        op: BinOp::Add
+                        op: BinOp::Add,
+                        span: None
                     })),
                     rhs: Box::new(Expr::Num(234)),
-                    op: BinOp::Sub
+                    op: BinOp::Sub,
+                    span: None
                 })),
                 rhs: Box::new(Expr::Bin(BinExpr {
                     lhs: Box::new(Expr::Bin(BinExpr {
                         lhs: Box::new(Expr::Num(234)),
                         rhs: Box::new(Expr::Num(243)),
-                        op: BinOp::Sub
+                        op: BinOp::Sub,
+                        span: None
                     })),
                     rhs: Box::new(Expr::Num(4232)),
-                    op: BinOp::Add
+                    op: BinOp::Add,
+                    span: None
                 })),
-                op: BinOp::GreaterEqSign
+                op: BinOp::GreaterEqSign,
+                span: None
             })
         );
 
@@ -808,30 +1519,38 @@ mod expr_tests {
                             rhs: Box::new(Expr::Bin(BinExpr {
                                 lhs: Box::new(Expr::Num(324)),
                                 rhs: Box::new(Expr::Num(23)),
-                                op: BinOp::Div
+                                op: BinOp::Div,
+                                span: None
                             })),
-                            op: BinOp::Add
+                            op: BinOp::Add,
+                            span: None
                         })),
                         rhs: Box::new(Expr::Bin(BinExpr {
                             lhs: Box::new(Expr::Num(25)),
                             rhs: Box::new(Expr::Num(234)),
-                            op: BinOp::Div
+                            op: BinOp::Div,
+                            span: None
                         })),
-                        op: BinOp::Add
+                        op: BinOp::Add,
+                        span: None
                     })),
                     rhs: Box::new(Expr::Num(234)),
-                    op: BinOp::Sub
+                    op: BinOp::Sub,
+                    span: None
                 })),
                 rhs: Box::new(Expr::Bin(BinExpr {
                     lhs: Box::new(Expr::Bin(BinExpr {
                         lhs: Box::new(Expr::Num(234)),
                         rhs: Box::new(Expr::Num(243)),
-                        op: BinOp::Sub
+                        op: BinOp::Sub,
+                        span: None
                     })),
                     rhs: Box::new(Expr::Num(4232)),
-                    op: BinOp::Add
+                    op: BinOp::Add,
+                    span: None
                 })),
-                op: BinOp::GreaterSign
+                op: BinOp::GreaterSign,
+                span: None
             })
         );
 
@@ -850,30 +1569,38 @@ mod expr_tests {
                             rhs: Box::new(Expr::Bin(BinExpr {
                                 lhs: Box::new(Expr::Num(324)),
                                 rhs: Box::new(Expr::Num(23)),
-                                op: BinOp::Div
+                                op: BinOp::Div,
+                                span: None
                             })),
-                            op: BinOp::Add
+                            op: BinOp::Add,
+                            span: None
                         })),
                         rhs: Box::new(Expr::Bin(BinExpr {
                             lhs: Box::new(Expr::Num(25)),
                             rhs: Box::new(Expr::Num(234)),
-                            op: BinOp::Div
+                            op: BinOp::Div,
+                            span: None
                         })),
-                        op: BinOp::Add
+                        op: BinOp::Add,
+                        span: None
                     })),
                     rhs: Box::new(Expr::Num(234)),
-                    op: BinOp::Sub
+                    op: BinOp::Sub,
+                    span: None
                 })),
                 rhs: Box::new(Expr::Bin(BinExpr {
                     lhs: Box::new(Expr::Bin(BinExpr {
                         lhs: Box::new(Expr::Num(234)),
                         rhs: Box::new(Expr::Num(243)),
-                        op: BinOp::Sub
+                        op: BinOp::Sub,
+                        span: None
                     })),
                     rhs: Box::new(Expr::Num(4232)),
-                    op: BinOp::Add
+                    op: BinOp::Add,
+                    span: None
                 })),
-                op: BinOp::LessEqSign
+                op: BinOp::LessEqSign,
+                span: None
             })
         );
 
@@ -892,30 +1619,38 @@ mod expr_tests {
                             rhs: Box::new(Expr::Bin(BinExpr {
                                 lhs: Box::new(Expr::Num(324)),
                                 rhs: Box::new(Expr::Num(23)),
-                                op: BinOp::Div
+                                op: BinOp::Div,
+                                span: None
                             })),
-                            op: BinOp::Add
+                            op: BinOp::Add,
+                            span: None
                         })),
                         rhs: Box::new(Expr::Bin(BinExpr {
                             lhs: Box::new(Expr::Num(25)),
                             rhs: Box::new(Expr::Num(234)),
-                            op: BinOp::Div
+                            op: BinOp::Div,
+                            span: None
                         })),
-                        op: BinOp::Add
+                        op: BinOp::Add,
+                        span: None
                     })),
                     rhs: Box::new(Expr::Num(234)),
-                    op: BinOp::Sub
+                    op: BinOp::Sub,
+                    span: None
                 })),
                 rhs: Box::new(Expr::Bin(BinExpr {
                     lhs: Box::new(Expr::Bin(BinExpr {
                         lhs: Box::new(Expr::Num(234)),
                         rhs: Box::new(Expr::Num(243)),
-                        op: BinOp::Sub
+                        op: BinOp::Sub,
+                        span: None
                     })),
                     rhs: Box::new(Expr::Num(4232)),
-                    op: BinOp::Add
+                    op: BinOp::Add,
+                    span: None
                 })),
-                op: BinOp::LessSign
+                op: BinOp::LessSign,
+                span: None
             })
         );
     }
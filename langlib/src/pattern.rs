@@ -0,0 +1,194 @@
+use std::fmt::Display;
+
+use crate::{
+    expr::{Expr, Var},
+    lexer::span::Span,
+};
+
+/// A pattern that values can be matched against, which can bind parts of the value to new variables.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Pattern {
+    /// `_`, which matches anything.
+    Wildcard,
+    /// A number, string, boolean or `null`, which matches values equal to it.
+    Literal(Expr),
+    /// A name, which matches anything and binds the value to a new variable.
+    Binding(Binding),
+    /// Matches lists whose items match `items`. Without a rest pattern the lengths have to be equal, and with one
+    /// the items that are left over are matched against it as a new list (e.g `[first, ...rest]`).
+    List {
+        items: Vec<Pattern>,
+        rest: Option<Box<Pattern>>,
+    },
+    /// Matches maps that have all of the keys, with values that match their patterns (e.g `{"name": n}`). Other
+    /// keys are ignored.
+    Map(Vec<(Expr, Pattern)>),
+    /// Matches a variant of an enum, and the values it holds if the fields are given (e.g `Shape.Circle(r)`).
+    /// Without the fields, any value of the variant matches.
+    Variant {
+        enum_var: Var,
+        variant: String,
+        fields: Option<Vec<Pattern>>,
+    },
+    /// A list item or map entry with a value to use when it's missing (e.g `[a, b = 0]`, `{name = "anon"}`). The
+    /// default is only evaluated if it's needed.
+    Default(Box<Pattern>, Expr),
+}
+
+impl Pattern {
+    /// Returns the names of every variable the pattern binds, in the order they appear.
+    pub fn idents(&self) -> Vec<String> {
+        self.bindings()
+            .into_iter()
+            .map(|binding| binding.ident.clone())
+            .collect()
+    }
+
+    /// Returns every variable the pattern binds, in the order they appear.
+    pub fn bindings(&self) -> Vec<&Binding> {
+        match self {
+            Pattern::Wildcard | Pattern::Literal(_) => Vec::new(),
+            Pattern::Binding(binding) => vec![binding],
+            Pattern::List { items, rest } => items
+                .iter()
+                .chain(rest.as_deref())
+                .flat_map(Pattern::bindings)
+                .collect(),
+            Pattern::Map(entries) => entries.iter().flat_map(|(_, v)| v.bindings()).collect(),
+            Pattern::Variant { fields, .. } => fields
+                .iter()
+                .flatten()
+                .flat_map(Pattern::bindings)
+                .collect(),
+            Pattern::Default(pattern, _) => pattern.bindings(),
+        }
+    }
+}
+
+/// Writes a list of patterns separated by commas.
+fn write_patterns<'a>(
+    f: &mut std::fmt::Formatter<'_>,
+    patterns: impl Iterator<Item = &'a Pattern>,
+) -> std::fmt::Result {
+    for (i, pattern) in patterns.enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+
+        write!(f, "{pattern}")?;
+    }
+
+    Ok(())
+}
+
+/// Writes a literal used in a pattern or as the key of a map pattern, which can always be evaluated on its own.
+fn write_literal(f: &mut std::fmt::Formatter<'_>, expr: &Expr) -> std::fmt::Result {
+    match expr {
+        Expr::Str(string) => write!(f, "{string:?}"),
+        expr => match expr.eval() {
+            Ok(v) => write!(f, "{v}"),
+            Err(_) => write!(f, "?"),
+        },
+    }
+}
+
+// Patterns are shown in errors, so they are written the way they appear in the code. Default values aren't
+// shown, since they can be any expression.
+impl Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pattern::Wildcard => write!(f, "_"),
+            Pattern::Literal(expr) => write_literal(f, expr),
+            Pattern::Binding(binding) => write!(f, "{}", binding.ident),
+            Pattern::List { items, rest } => {
+                write!(f, "[")?;
+                write_patterns(f, items.iter())?;
+
+                if let Some(rest) = rest {
+                    if !items.is_empty() {
+                        write!(f, ", ")?;
+                    }
+
+                    match **rest {
+                        Pattern::Wildcard => write!(f, "...")?,
+                        ref rest => write!(f, "...{rest}")?,
+                    }
+                }
+
+                write!(f, "]")
+            }
+            Pattern::Map(entries) => {
+                write!(f, "{{")?;
+
+                for (i, (key, v)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write_literal(f, key)?;
+                    write!(f, ": {v}")?;
+                }
+
+                write!(f, "}}")
+            }
+            Pattern::Variant {
+                enum_var,
+                variant,
+                fields,
+            } => {
+                write!(f, "{}.{variant}", enum_var.ident)?;
+
+                if let Some(fields) = fields {
+                    write!(f, "(")?;
+                    write_patterns(f, fields.iter())?;
+                    write!(f, ")")?;
+                }
+
+                Ok(())
+            }
+            Pattern::Default(pattern, _) => write!(f, "{pattern} = ..."),
+        }
+    }
+}
+
+/// A variable introduced by a declaration or a pattern. Unlike a `Var`, it's always stored in the innermost
+/// scope, so the resolver only has to fill in the index of its slot.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Binding {
+    pub ident: String,
+    /// The slot the variable is stored in. This is filled in by the resolver, and stays `None` for globals.
+    pub slot: Option<usize>,
+    /// Where the name is in the source, if the parser was given spans.
+    pub span: Option<Span>,
+}
+
+impl Binding {
+    pub fn new(ident: &str) -> Self {
+        Self::with_span(ident, None)
+    }
+
+    pub fn with_span(ident: &str, span: Option<Span>) -> Self {
+        Self {
+            ident: ident.to_owned(),
+            slot: None,
+            span,
+        }
+    }
+}
+
+/// A `match`, which runs the body of the first arm whose pattern matches the value. The body is an `Expr` when
+/// the match is used as an expression, and a `Stmt` when it's used as a statement.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Match<B> {
+    pub val: Expr,
+    pub arms: Vec<Arm<B>>,
+}
+
+/// One arm of a `match` (e.g `[x, ...] if x > 0 => x`).
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Arm<B> {
+    pub pattern: Pattern,
+    /// A condition that also has to hold for the arm to be picked. It can use the pattern's variables.
+    pub guard: Option<Expr>,
+    pub body: B,
+}
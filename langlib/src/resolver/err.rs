@@ -0,0 +1,85 @@
+use crate::lexer::span::{location, Span};
+
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+/// Error enum for the `Resolver` struct.
+pub enum ResolverErr {
+    #[error("Variable \"{0}\" already exists in this scope{}.", location(.1))]
+    VarRedefine(String, Option<Span>),
+
+    #[error("Can't read the local variable \"{0}\" in its own initializer{}.", location(.1))]
+    ReadInOwnInitializer(String, Option<Span>),
+
+    #[error("Can't use the parameter \"{0}\" in a default, since it isn't bound until later{}.", location(.1))]
+    UnboundParam(String, Option<Span>),
+
+    #[error("Can't assign to \"{0}\", since it was never declared{}.", location(.1))]
+    UndeclaredAssignment(String, Option<Span>),
+
+    #[error("Can't assign to \"{0}\", since it's a constant{}.", location(.1))]
+    ConstAssignment(String, Option<Span>),
+
+    #[error("Can't return from top-level code{}.", location(.0))]
+    TopLevelReturn(Option<Span>),
+
+    #[error("Can't return a value from \"init\"{}.", location(.0))]
+    ReturnFromInit(Option<Span>),
+
+    #[error("Can't use \"break\" outside of a loop{}.", location(.0))]
+    BreakOutsideLoop(Option<Span>),
+
+    #[error("Can't use \"continue\" outside of a loop{}.", location(.0))]
+    ContinueOutsideLoop(Option<Span>),
+
+    #[error("Can't use \"this\" outside of a class{}.", location(.0))]
+    ThisOutsideClass(Option<Span>),
+
+    #[error("Can't use \"super\" outside of a class{}.", location(.0))]
+    SuperOutsideClass(Option<Span>),
+
+    #[error("Can't use \"super\" in a class with no superclass{}.", location(.0))]
+    SuperWithoutSuperclass(Option<Span>),
+
+    #[error("The class \"{0}\" can't inherit from itself{}.", location(.1))]
+    InheritFromSelf(String, Option<Span>),
+
+    #[error("Can't inherit from \"{0}\", since it isn't a class{}.", location(.1))]
+    InheritFromNonClass(String, Option<Span>),
+
+    #[error("The trait \"{0}\" was never declared{}.", location(.1))]
+    UndefinedTrait(String, Option<Span>),
+
+    #[error("The trait \"{0}\" is declared more than once{}.", location(.1))]
+    TraitRedefine(String, Option<Span>),
+
+    #[error("The enum \"{enum_name}\" has more than one variant called \"{variant}\"{}.", location(.span))]
+    VariantRedefine {
+        enum_name: String,
+        variant: String,
+        span: Option<Span>,
+    },
+
+    #[error("The pattern for \"{variant}\" has {found} fields, but the variant holds {expected}{}.", location(.span))]
+    VariantFieldCount {
+        variant: String,
+        expected: usize,
+        found: usize,
+        span: Option<Span>,
+    },
+
+    #[error("The class \"{class}\" implements \"{trait_name}\", but has no \"{method}\" method{}.", location(.span))]
+    MissingTraitMethod {
+        class: String,
+        trait_name: String,
+        method: String,
+        span: Option<Span>,
+    },
+
+    #[error("The method \"{method}\" of \"{class}\" has to take {arity} arguments to implement \"{trait_name}\"{}.", location(.span))]
+    TraitArityMismatch {
+        class: String,
+        trait_name: String,
+        method: String,
+        arity: usize,
+        span: Option<Span>,
+    },
+}
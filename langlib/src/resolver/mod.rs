@@ -0,0 +1,785 @@
+pub mod err;
+mod tests;
+
+use std::rc::Rc;
+
+use ahash::{AHashMap, AHashSet};
+
+use crate::{
+    class::{ClassDecl, TraitDecl},
+    expr::{Expr, Slot, Var},
+    func::{Func, FuncDecl},
+    lexer::span::Span,
+    pattern::{Binding, Match, Pattern},
+    stmt::Stmt,
+};
+
+use self::err::ResolverErr;
+
+/// A static pass that runs between the parser and the interpreter. It works out which scope every variable
+/// reference points to, and reports mistakes that can be caught before the code runs.
+#[derive(Debug, Default)]
+pub struct Resolver {
+    /// The scopes that are currently open, starting with the global scope.
+    scopes: Vec<AHashMap<String, Local>>,
+    /// Every variable declared at the top level, including the ones declared further down in the code.
+    globals: AHashSet<String>,
+    /// The index of the first scope belonging to the function currently being resolved, if there is one.
+    func_scope: Option<usize>,
+    /// How many loops the current statement is nested in, within the current function.
+    loop_depth: usize,
+    /// Whether the current function is the `init` method of a class.
+    in_init: bool,
+    /// The kind of class the current statement is in, if it's in one.
+    class: Option<ClassKind>,
+    /// Every trait declared so far. Top-level traits are collected up front, so classes can implement traits
+    /// declared further down.
+    traits: AHashMap<String, TraitDecl>,
+    /// The methods of every class resolved so far, including the ones they inherit. The variable holding a class
+    /// points into this with `Local::methods`.
+    class_methods: Vec<AHashMap<String, Arity>>,
+    /// How many fields each variant of every enum resolved so far holds. The variable holding an enum points into
+    /// this with `Local::variants`.
+    enum_variants: Vec<AHashMap<String, usize>>,
+}
+
+impl Resolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolves a whole program.
+    pub fn resolve(&mut self, stmts: &mut [Stmt]) -> Result<(), ResolverErr> {
+        // Globals can be used by functions before they are declared, so collect them all up front.
+        self.globals = stmts
+            .iter()
+            .flat_map(|stmt| match stmt {
+                Stmt::Declaration(declaration) => vec![declaration.name.ident.clone()],
+                Stmt::Destructure(pattern, ..) => pattern.idents(),
+                Stmt::Class(class) => vec![class.name.ident.clone()],
+                Stmt::Enum(enum_decl) => vec![enum_decl.name.ident.clone()],
+                _ => Vec::new(),
+            })
+            .collect();
+
+        self.scopes = vec![AHashMap::new()];
+        self.func_scope = None;
+        self.loop_depth = 0;
+        self.in_init = false;
+        self.class = None;
+        self.class_methods = Vec::new();
+        self.enum_variants = Vec::new();
+
+        self.traits = AHashMap::new();
+
+        for stmt in stmts.iter() {
+            if let Stmt::Trait(trait_decl) = stmt {
+                self.declare_trait(trait_decl)?;
+            }
+        }
+
+        self.resolve_stmts(stmts)
+    }
+
+    fn resolve_stmts(&mut self, stmts: &mut [Stmt]) -> Result<(), ResolverErr> {
+        stmts
+            .iter_mut()
+            .try_for_each(|stmt| self.resolve_stmt(stmt))
+    }
+
+    fn resolve_stmt(&mut self, stmt: &mut Stmt) -> Result<(), ResolverErr> {
+        match stmt {
+            Stmt::Declaration(declaration) => {
+                self.declare_binding(&mut declaration.name)?;
+
+                // Functions are allowed to refer to themselves, so they are defined before their body is resolved.
+                if let Expr::Func(_) = declaration.val {
+                    self.define(&declaration.name.ident);
+                    self.resolve_expr(&mut declaration.val)?;
+                } else {
+                    self.resolve_expr(&mut declaration.val)?;
+                    self.define(&declaration.name.ident);
+                }
+
+                if is_literal(&declaration.val) {
+                    self.set_holds(&declaration.name.ident, Holds::NonClass);
+                }
+
+                if declaration.constant {
+                    if let Some(local) = self.find_local(&declaration.name.ident) {
+                        local.constant = true;
+                    }
+                }
+            }
+            Stmt::Destructure(pattern, expr, constant) => {
+                self.resolve_expr(expr)?;
+                self.resolve_pattern(pattern)?;
+
+                if *constant {
+                    for ident in pattern.idents() {
+                        if let Some(local) = self.find_local(&ident) {
+                            local.constant = true;
+                        }
+                    }
+                }
+            }
+            Stmt::Assignment(var, expr) => {
+                self.resolve_expr(expr)?;
+                self.resolve_var(var)?;
+
+                // Globals declared further down aren't in scope yet, so the interpreter checks those
+                if self
+                    .find_local(&var.ident)
+                    .is_some_and(|local| local.constant)
+                {
+                    return Err(ResolverErr::ConstAssignment(var.ident.clone(), var.span));
+                }
+
+                // The variable could hold anything from now on
+                if let Some(local) = self.find_local(&var.ident) {
+                    local.holds = Holds::Unknown;
+                    local.methods = None;
+                    local.variants = None;
+                }
+
+                if var.slot.is_none()
+                    && !self.globals.contains(&var.ident)
+                    && !self.scopes[0].contains_key(&var.ident)
+                {
+                    return Err(ResolverErr::UndeclaredAssignment(
+                        var.ident.clone(),
+                        var.span,
+                    ));
+                }
+            }
+            Stmt::IndexAssignment(list, index, expr) => {
+                self.resolve_expr(expr)?;
+                self.resolve_expr(list)?;
+                self.resolve_expr(index)?;
+            }
+            Stmt::SetProperty(instance, _, expr) => {
+                self.resolve_expr(expr)?;
+                self.resolve_expr(instance)?;
+            }
+            Stmt::Class(class) => {
+                self.declare_binding(&mut class.name)?;
+                self.define(&class.name.ident);
+                self.set_holds(&class.name.ident, Holds::Class);
+
+                self.resolve_class(class)?;
+            }
+            Stmt::Match(parsed) => self.resolve_match(parsed, Self::resolve_stmt)?,
+            Stmt::Enum(enum_decl) => {
+                let mut seen = AHashSet::new();
+
+                if let Some(variant) = enum_decl
+                    .variants
+                    .iter()
+                    .find(|variant| !seen.insert(&variant.ident))
+                {
+                    return Err(ResolverErr::VariantRedefine {
+                        enum_name: enum_decl.name.ident.clone(),
+                        variant: variant.ident.clone(),
+                        span: enum_decl.name.span,
+                    });
+                }
+
+                self.declare_binding(&mut enum_decl.name)?;
+                self.define(&enum_decl.name.ident);
+                self.set_holds(&enum_decl.name.ident, Holds::NonClass);
+
+                let index = self.enum_variants.len();
+                self.enum_variants.push(
+                    enum_decl
+                        .variants
+                        .iter()
+                        .map(|variant| (variant.ident.clone(), variant.fields.len()))
+                        .collect(),
+                );
+
+                if let Some(local) = self.find_local(&enum_decl.name.ident) {
+                    local.variants = Some(index);
+                }
+            }
+            // Top-level traits have already been collected
+            Stmt::Trait(trait_decl) if self.scopes.len() > 1 => self.declare_trait(trait_decl)?,
+            Stmt::Trait(_) => {}
+            Stmt::Print(expr) | Stmt::Expr(expr) => self.resolve_expr(expr)?,
+            Stmt::Block(stmts) => {
+                self.scopes.push(AHashMap::new());
+                let result = self.resolve_stmts(stmts);
+                self.scopes.pop();
+
+                result?
+            }
+            Stmt::If(condition, block, else_block) => {
+                self.resolve_expr(condition)?;
+                self.resolve_stmt(block)?;
+
+                if let Some(else_block) = else_block {
+                    self.resolve_stmt(else_block)?;
+                }
+            }
+            Stmt::While(condition, block, increment) => {
+                self.resolve_expr(condition)?;
+
+                self.loop_depth += 1;
+                let result = self.resolve_stmt(block);
+                self.loop_depth -= 1;
+
+                result?;
+
+                if let Some(increment) = increment {
+                    self.resolve_stmt(increment)?;
+                }
+            }
+            Stmt::ForIn(for_in) => {
+                self.resolve_expr(&mut for_in.iterable)?;
+
+                // The loop variables live in their own scope, around the body
+                self.scopes.push(AHashMap::new());
+                self.loop_depth += 1;
+
+                let result = for_in.idents.iter().try_for_each(|ident| {
+                    self.declare(ident, for_in.span)?;
+                    self.define(ident);
+
+                    Ok(())
+                });
+                let result = result.and_then(|_| self.resolve_stmt(&mut for_in.body));
+
+                self.loop_depth -= 1;
+                self.scopes.pop();
+
+                result?
+            }
+            Stmt::Break(span) if self.loop_depth == 0 => {
+                return Err(ResolverErr::BreakOutsideLoop(*span))
+            }
+            Stmt::Continue(span) if self.loop_depth == 0 => {
+                return Err(ResolverErr::ContinueOutsideLoop(*span))
+            }
+            Stmt::Break(_) | Stmt::Continue(_) => {}
+            Stmt::Return(expr, span) => {
+                if self.func_scope.is_none() {
+                    return Err(ResolverErr::TopLevelReturn(*span));
+                }
+
+                // `init` always returns the instance, so any other value would be thrown away
+                if self.in_init && *expr != Expr::Null {
+                    return Err(ResolverErr::ReturnFromInit(*span));
+                }
+
+                self.resolve_expr(expr)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn resolve_expr(&mut self, expr: &mut Expr) -> Result<(), ResolverErr> {
+        match expr {
+            Expr::Var(var) if var.ident == "this" && self.class.is_none() => {
+                return Err(ResolverErr::ThisOutsideClass(var.span))
+            }
+            Expr::Super(var, _) => match self.class {
+                Some(ClassKind::Subclass) => self.resolve_var(var)?,
+                Some(ClassKind::Class) => {
+                    return Err(ResolverErr::SuperWithoutSuperclass(var.span))
+                }
+                None => return Err(ResolverErr::SuperOutsideClass(var.span)),
+            },
+            Expr::Var(var) => self.resolve_var(var)?,
+            Expr::Bin(bin_expr) => {
+                self.resolve_expr(&mut bin_expr.lhs)?;
+                self.resolve_expr(&mut bin_expr.rhs)?;
+            }
+            Expr::Unary(_, expr, _) => self.resolve_expr(expr)?,
+            Expr::Funcall(callee, args, named) | Expr::OptionalCall(callee, args, named) => {
+                self.resolve_expr(callee)?;
+
+                args.iter_mut()
+                    .chain(named.iter_mut().map(|(_, expr)| expr))
+                    .try_for_each(|expr| self.resolve_expr(expr))?;
+            }
+            Expr::Func(func) => self.resolve_func(func, false)?,
+            Expr::List(items) => items
+                .iter_mut()
+                .try_for_each(|item| self.resolve_expr(item))?,
+            Expr::Map(entries) => entries.iter_mut().try_for_each(|(key, v)| {
+                self.resolve_expr(key)?;
+                self.resolve_expr(v)
+            })?,
+            Expr::Range(start, end, _) => {
+                self.resolve_expr(start)?;
+                self.resolve_expr(end)?;
+            }
+            Expr::Index(list, index) => {
+                self.resolve_expr(list)?;
+                self.resolve_expr(index)?;
+            }
+            Expr::Get(instance, _) | Expr::OptionalGet(instance, _) => {
+                self.resolve_expr(instance)?
+            }
+            Expr::Chain(chain) => self.resolve_expr(chain)?,
+            Expr::Ternary(condition, then_branch, else_branch) => {
+                self.resolve_expr(condition)?;
+                self.resolve_expr(then_branch)?;
+                self.resolve_expr(else_branch)?;
+            }
+            Expr::Match(parsed) => self.resolve_match(parsed, Self::resolve_expr)?,
+            Expr::Slice(list, start, end) => {
+                self.resolve_expr(list)?;
+
+                for bound in [start, end].into_iter().flatten() {
+                    self.resolve_expr(bound)?;
+                }
+            }
+            Expr::Num(_) | Expr::BigNum(_) | Expr::Str(_) | Expr::Bool(_) | Expr::Null => {}
+        }
+
+        Ok(())
+    }
+
+    /// Resolves a `match`. Every arm has its own scope, holding the variables bound by its pattern.
+    fn resolve_match<B>(
+        &mut self,
+        parsed: &mut Match<B>,
+        resolve_body: fn(&mut Self, &mut B) -> Result<(), ResolverErr>,
+    ) -> Result<(), ResolverErr> {
+        self.resolve_expr(&mut parsed.val)?;
+
+        parsed.arms.iter_mut().try_for_each(|arm| {
+            self.scopes.push(AHashMap::new());
+
+            let result = self.resolve_pattern(&mut arm.pattern).and_then(|_| {
+                if let Some(guard) = &mut arm.guard {
+                    self.resolve_expr(guard)?;
+                }
+
+                resolve_body(self, &mut arm.body)
+            });
+
+            self.scopes.pop();
+
+            result
+        })
+    }
+
+    /// Declares the variables bound by a pattern in the innermost scope, in the order they appear.
+    fn resolve_pattern(&mut self, pattern: &mut Pattern) -> Result<(), ResolverErr> {
+        match pattern {
+            Pattern::Wildcard => {}
+            Pattern::Literal(expr) => self.resolve_expr(expr)?,
+            Pattern::Binding(binding) => {
+                self.declare_binding(binding)?;
+                self.define(&binding.ident);
+            }
+            Pattern::List { items, rest } => {
+                for item in items
+                    .iter_mut()
+                    .chain(rest.iter_mut().map(|rest| &mut **rest))
+                {
+                    self.resolve_pattern(item)?;
+                }
+            }
+            Pattern::Map(entries) => entries.iter_mut().try_for_each(|(key, v)| {
+                self.resolve_expr(key)?;
+                self.resolve_pattern(v)
+            })?,
+            Pattern::Variant {
+                enum_var,
+                variant,
+                fields,
+            } => {
+                self.resolve_var(enum_var)?;
+
+                // The interpreter checks the fields of enums that aren't known until the code runs
+                let expected = self
+                    .find_local(&enum_var.ident)
+                    .and_then(|local| local.variants)
+                    .and_then(|i| self.enum_variants[i].get(variant.as_str()).copied());
+
+                if let (Some(expected), Some(fields)) = (expected, &fields) {
+                    if fields.len() != expected {
+                        return Err(ResolverErr::VariantFieldCount {
+                            variant: format!("{}.{variant}", enum_var.ident),
+                            expected,
+                            found: fields.len(),
+                            span: enum_var.span,
+                        });
+                    }
+                }
+
+                for field in fields.iter_mut().flatten() {
+                    self.resolve_pattern(field)?;
+                }
+            }
+            // The default can't see the variable it's the default for
+            Pattern::Default(pattern, default) => {
+                self.resolve_expr(default)?;
+                self.resolve_pattern(pattern)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the methods of a class. Every method gets a scope of its own holding `this`, around the scope of
+    /// its parameters, which matches the environment a method is bound in. Subclasses have one more scope around
+    /// all of their methods, holding `super`.
+    fn resolve_class(&mut self, class: &mut ClassDecl) -> Result<(), ResolverErr> {
+        if let Some(superclass) = &mut class.superclass {
+            if superclass.ident == class.name.ident {
+                return Err(ResolverErr::InheritFromSelf(
+                    class.name.ident.clone(),
+                    superclass.span,
+                ));
+            }
+
+            if self.holds(&superclass.ident) == Some(Holds::NonClass) {
+                return Err(ResolverErr::InheritFromNonClass(
+                    superclass.ident.clone(),
+                    superclass.span,
+                ));
+            }
+
+            self.resolve_var(superclass)?;
+
+            self.scopes.push(AHashMap::new());
+            self.declare("super", None)?;
+            self.define("super");
+        }
+
+        let kind = match class.superclass {
+            Some(_) => ClassKind::Subclass,
+            None => ClassKind::Class,
+        };
+        let enclosing = self.class.replace(kind);
+
+        let result = class.methods.iter_mut().try_for_each(|(ident, method)| {
+            self.scopes.push(AHashMap::new());
+            self.declare("this", None)?;
+            self.define("this");
+
+            let result = self.resolve_func(method, ident == "init");
+            self.scopes.pop();
+
+            result
+        });
+
+        self.class = enclosing;
+
+        if class.superclass.is_some() {
+            self.scopes.pop();
+        }
+
+        result?;
+
+        self.check_traits(class)
+    }
+
+    /// Checks that a class defines every method of the traits it implements, and that the methods accept the
+    /// number of arguments the traits ask for.
+    fn check_traits(&mut self, class: &ClassDecl) -> Result<(), ResolverErr> {
+        let inherited = match &class.superclass {
+            Some(superclass) => self
+                .find_local(&superclass.ident)
+                .and_then(|local| local.methods)
+                .map(|i| self.class_methods[i].clone()),
+            None => Some(AHashMap::new()),
+        };
+
+        // Methods can only be missing if everything the class inherits is known
+        let known = inherited.is_some();
+
+        let mut methods = inherited.unwrap_or_default();
+
+        methods.extend(class.methods.iter().map(|(ident, func)| {
+            let arity = Arity {
+                min: func.decl.min_args(),
+                max: func.decl.max_args(),
+            };
+
+            (ident.clone(), arity)
+        }));
+
+        for trait_name in &class.traits {
+            let trait_decl = self
+                .traits
+                .get(trait_name)
+                .ok_or_else(|| ResolverErr::UndefinedTrait(trait_name.clone(), class.name.span))?;
+
+            for (method, arity) in &trait_decl.methods {
+                match methods.get(method) {
+                    Some(found) if !found.accepts(*arity) => {
+                        return Err(ResolverErr::TraitArityMismatch {
+                            class: class.name.ident.clone(),
+                            trait_name: trait_name.clone(),
+                            method: method.clone(),
+                            arity: *arity,
+                            span: class.name.span,
+                        })
+                    }
+                    None if known => {
+                        return Err(ResolverErr::MissingTraitMethod {
+                            class: class.name.ident.clone(),
+                            trait_name: trait_name.clone(),
+                            method: method.clone(),
+                            span: class.name.span,
+                        })
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if known {
+            let index = self.class_methods.len();
+            self.class_methods.push(methods);
+
+            if let Some(local) = self.find_local(&class.name.ident) {
+                local.methods = Some(index);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Makes a trait available to the classes resolved after it.
+    fn declare_trait(&mut self, trait_decl: &TraitDecl) -> Result<(), ResolverErr> {
+        if self.traits.contains_key(&trait_decl.ident) {
+            return Err(ResolverErr::TraitRedefine(
+                trait_decl.ident.clone(),
+                trait_decl.span,
+            ));
+        }
+
+        self.traits
+            .insert(trait_decl.ident.clone(), trait_decl.clone());
+
+        Ok(())
+    }
+
+    /// Resolves a function, which is the `init` method of a class if `init` is set. The parameters and the
+    /// statements of the body share a single scope, the same way they do when the function is called.
+    fn resolve_func(&mut self, func: &mut Func, init: bool) -> Result<(), ResolverErr> {
+        // Loops outside of the function can't be broken out of from inside it
+        let enclosing = self.func_scope.replace(self.scopes.len());
+        let enclosing_loops = std::mem::take(&mut self.loop_depth);
+        let enclosing_init = std::mem::replace(&mut self.in_init, init);
+        self.scopes.push(AHashMap::new());
+
+        let result = self.resolve_func_body(Rc::make_mut(&mut func.decl));
+
+        self.scopes.pop();
+        self.in_init = enclosing_init;
+        self.loop_depth = enclosing_loops;
+        self.func_scope = enclosing;
+
+        result
+    }
+
+    fn resolve_func_body(&mut self, func: &mut FuncDecl) -> Result<(), ResolverErr> {
+        // Every parameter is declared up front, so a default that uses its own parameter or a later one is caught
+        // instead of being taken for a global.
+        for param in &func.args {
+            self.declare(&param.ident, param.span)?;
+        }
+
+        if let Some(rest) = &func.rest {
+            self.declare(rest, None)?;
+        }
+
+        // Defaults are evaluated before their own parameter is defined.
+        for param in func.args.iter_mut() {
+            if let Some(default) = &mut param.default {
+                self.resolve_expr(default).map_err(|err| match err {
+                    ResolverErr::ReadInOwnInitializer(ident, span) => {
+                        ResolverErr::UnboundParam(ident, span)
+                    }
+                    err => err,
+                })?;
+            }
+
+            self.define(&param.ident);
+        }
+
+        if let Some(rest) = &func.rest {
+            self.define(rest);
+        }
+
+        match &mut func.instructions {
+            Stmt::Block(stmts) => self.resolve_stmts(stmts),
+            stmt => self.resolve_stmt(stmt),
+        }
+    }
+
+    /// Works out which scope the variable was declared in, and which slot of that scope it's stored in. Variables
+    /// that aren't found in any local scope are assumed to be globals.
+    fn resolve_var(&mut self, var: &mut Var) -> Result<(), ResolverErr> {
+        var.slot = None;
+
+        let found = self
+            .scopes
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(i, scope)| scope.get(&var.ident).map(|local| (i, *local)));
+
+        let (i, local) = match found {
+            Some(found) => found,
+            None => return Ok(()),
+        };
+
+        // Reading a variable that's still being initialized is only fine from inside a function, since the
+        // function can't be called before the initializer is done.
+        if !local.defined && self.func_scope.is_none_or(|func_scope| i >= func_scope) {
+            return Err(ResolverErr::ReadInOwnInitializer(
+                var.ident.clone(),
+                var.span,
+            ));
+        }
+
+        if i > 0 {
+            var.slot = Some(Slot {
+                depth: self.scopes.len() - 1 - i,
+                index: local.index,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Adds a variable to the innermost scope, without marking it as ready to use. Returns the slot the variable
+    /// is stored in, or `None` if it's a global. The span is where the variable is declared, for error messages.
+    fn declare(&mut self, ident: &str, span: Option<Span>) -> Result<Option<usize>, ResolverErr> {
+        let is_global = self.scopes.len() == 1;
+        let scope = self
+            .scopes
+            .last_mut()
+            .expect("there is always a global scope");
+
+        if scope.contains_key(ident) {
+            return Err(ResolverErr::VarRedefine(ident.to_owned(), span));
+        }
+
+        let index = scope.len();
+
+        scope.insert(
+            ident.to_owned(),
+            Local {
+                defined: false,
+                index,
+                holds: Holds::Unknown,
+                constant: false,
+                methods: None,
+                variants: None,
+            },
+        );
+
+        Ok((!is_global).then_some(index))
+    }
+
+    /// Declares the variable introduced by a declaration or pattern, and fills in its slot.
+    fn declare_binding(&mut self, binding: &mut Binding) -> Result<(), ResolverErr> {
+        binding.slot = self.declare(&binding.ident, binding.span)?;
+
+        Ok(())
+    }
+
+    /// Marks a variable in the innermost scope as ready to use.
+    fn define(&mut self, ident: &str) {
+        if let Some(local) = self
+            .scopes
+            .last_mut()
+            .and_then(|scope| scope.get_mut(ident))
+        {
+            local.defined = true;
+        }
+    }
+
+    /// Finds the innermost variable called `ident`.
+    fn find_local(&mut self, ident: &str) -> Option<&mut Local> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(ident))
+    }
+
+    /// Gets what's known about the value of a variable, if the variable has been declared.
+    fn holds(&mut self, ident: &str) -> Option<Holds> {
+        self.find_local(ident).map(|local| local.holds)
+    }
+
+    /// Records what's known about the value of a variable.
+    fn set_holds(&mut self, ident: &str, holds: Holds) {
+        if let Some(local) = self.find_local(ident) {
+            local.holds = holds;
+        }
+    }
+}
+
+/// Returns true if the expression is a literal, so it can't evaluate to a class.
+fn is_literal(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Num(_)
+            | Expr::BigNum(_)
+            | Expr::Str(_)
+            | Expr::Bool(_)
+            | Expr::Null
+            | Expr::Func(_)
+            | Expr::List(_)
+            | Expr::Map(_)
+            | Expr::Range(..)
+    )
+}
+
+/// How many arguments a method accepts.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct Arity {
+    min: usize,
+    /// `None` if the method takes a rest parameter.
+    max: Option<usize>,
+}
+
+impl Arity {
+    fn accepts(&self, len: usize) -> bool {
+        len >= self.min && self.max.is_none_or(|max| len <= max)
+    }
+}
+
+/// The kinds of class a method can be in.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum ClassKind {
+    Class,
+    /// A class with a superclass, which can use `super`.
+    Subclass,
+}
+
+/// What the resolver knows about the value of a variable before the code runs.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Holds {
+    Class,
+    NonClass,
+    /// The value depends on something that only happens at runtime.
+    Unknown,
+}
+
+/// A variable declared in one of the resolver's scopes.
+#[derive(Debug, Clone, Copy)]
+struct Local {
+    /// Whether the variable's initializer has finished resolving.
+    defined: bool,
+    /// The slot the variable is stored in.
+    index: usize,
+    /// What the variable is known to hold.
+    holds: Holds,
+    /// Whether the variable was declared with `const`.
+    constant: bool,
+    /// Where the methods of the class the variable holds are in `Resolver::class_methods`, if they're all known.
+    methods: Option<usize>,
+    /// Where the variants of the enum the variable holds are in `Resolver::enum_variants`.
+    variants: Option<usize>,
+}
@@ -0,0 +1,354 @@
+#[cfg(test)]
+/// A module for resolver unit tests.
+mod resolver_tests {
+    use crate::{
+        expr::{Expr, Slot, Var},
+        lexer::Lexer,
+        parser::Parser,
+        resolver::{err::ResolverErr, Resolver},
+        stmt::Stmt,
+    };
+
+    /// Parses and resolves the given source code.
+    fn resolve(source: &str) -> Result<Vec<Stmt>, ResolverErr> {
+        let mut stmts = Parser::new(Lexer::new(source).tokenize().unwrap())
+            .get_statements()
+            .unwrap();
+
+        Resolver::new().resolve(&mut stmts)?;
+
+        Ok(stmts)
+    }
+
+    #[test]
+    fn test_resolve_depths() {
+        let stmts = resolve("let a = 1; { let b = 2; { print a; print b; } }").unwrap();
+
+        let inner = match &stmts[1] {
+            Stmt::Block(stmts) => match &stmts[1] {
+                Stmt::Block(stmts) => stmts,
+                _ => panic!(),
+            },
+            _ => panic!(),
+        };
+
+        // Globals are left unresolved
+        assert_eq!(inner[0], Stmt::Print(Expr::Var(Var::new("a"))));
+
+        assert_eq!(
+            inner[1],
+            Stmt::Print(Expr::Var(Var {
+                ident: "b".to_owned(),
+                slot: Some(Slot { depth: 1, index: 0 }),
+                span: None
+            }))
+        );
+    }
+
+    #[test]
+    fn test_read_in_own_initializer() {
+        assert_eq!(
+            resolve("{ let a = 1; { let a = a + 1; } }"),
+            Err(ResolverErr::ReadInOwnInitializer("a".to_owned(), None))
+        );
+
+        // Functions can refer to themselves, since they aren't called until they are defined.
+        assert!(resolve("{ let f = (n) => f(n); func g(n) { return g(n); } }").is_ok());
+    }
+
+    #[test]
+    fn test_top_level_return() {
+        assert_eq!(resolve("return 1;"), Err(ResolverErr::TopLevelReturn(None)));
+        assert_eq!(
+            resolve("{ return 1; }"),
+            Err(ResolverErr::TopLevelReturn(None))
+        );
+        assert!(resolve("func f() { { return 1; } }").is_ok());
+    }
+
+    #[test]
+    fn test_return_from_init() {
+        assert_eq!(
+            resolve("class A { init() { return 1; } }"),
+            Err(ResolverErr::ReturnFromInit(None))
+        );
+
+        // Returning early is fine, and so are functions and other methods inside the class
+        assert!(resolve("class A { init(x) { if (x) { return; } this.x = x; } }").is_ok());
+        assert!(resolve("class A { init() { this.f = () => 1; } get() { return 2; } }").is_ok());
+    }
+
+    #[test]
+    fn test_default_reads_param() {
+        assert_eq!(
+            resolve("let b = 1; func f(a = b, b = 2) { return a; }"),
+            Err(ResolverErr::UnboundParam("b".to_owned(), None))
+        );
+        assert_eq!(
+            resolve("let a = 1; func f(a = a) { return a; }"),
+            Err(ResolverErr::UnboundParam("a".to_owned(), None))
+        );
+
+        // Earlier parameters can be used, and so can later ones from inside a function
+        assert!(resolve("func f(a, b = a + 1, c = () => d, d = 1) { return b; }").is_ok());
+    }
+
+    #[test]
+    fn test_redefine() {
+        assert_eq!(
+            resolve("{ let a = 1; let a = 2; }"),
+            Err(ResolverErr::VarRedefine("a".to_owned(), None))
+        );
+        assert_eq!(
+            resolve("func f(a) { let a = 2; }"),
+            Err(ResolverErr::VarRedefine("a".to_owned(), None))
+        );
+
+        // Shadowing in an inner scope is fine
+        assert!(resolve("let a = 1; { let a = 2; }").is_ok());
+    }
+
+    #[test]
+    fn test_undeclared_assignment() {
+        assert_eq!(
+            resolve("{ b = 1; }"),
+            Err(ResolverErr::UndeclaredAssignment("b".to_owned(), None))
+        );
+
+        // Globals declared further down can still be assigned to from functions
+        assert!(resolve("func setup() { config = 1; } let config = 0; setup();").is_ok());
+    }
+
+    #[test]
+    fn test_const_assignment() {
+        assert_eq!(
+            resolve("const a = 1; a = 2;"),
+            Err(ResolverErr::ConstAssignment("a".to_owned(), None))
+        );
+        assert_eq!(
+            resolve("func f() { const a = 1; func g() { a = 2; } }"),
+            Err(ResolverErr::ConstAssignment("a".to_owned(), None))
+        );
+
+        assert_eq!(
+            resolve("{ const [a, {b}] = xs; b = 2; }"),
+            Err(ResolverErr::ConstAssignment("b".to_owned(), None))
+        );
+
+        // Shadowing a constant is fine
+        assert!(resolve("const a = 1; { let a = 2; a = 3; }").is_ok());
+    }
+
+    #[test]
+    fn test_break_outside_loop() {
+        assert_eq!(resolve("break;"), Err(ResolverErr::BreakOutsideLoop(None)));
+        assert_eq!(
+            resolve("while (true) { func f() { continue; } }"),
+            Err(ResolverErr::ContinueOutsideLoop(None))
+        );
+        assert!(resolve("while (true) { if (true) { break; } }").is_ok());
+    }
+
+    #[test]
+    fn test_this_outside_class() {
+        assert_eq!(
+            resolve("print this;"),
+            Err(ResolverErr::ThisOutsideClass(None))
+        );
+        assert_eq!(
+            resolve("func f() { return this.x; }"),
+            Err(ResolverErr::ThisOutsideClass(None))
+        );
+        assert!(resolve("class A { f() { return () => this; } }").is_ok());
+    }
+
+    #[test]
+    fn test_traits() {
+        let shape = "trait Shape { area(); scale(factor); }";
+
+        // Traits can be implemented before they're declared, and methods can be inherited
+        let inherited = "
+        class A { area() { return 1; } }
+        class B < A impl Shape { scale(f, g = 1) { return f; } }
+        ";
+        assert!(resolve(&format!("{inherited} {shape}")).is_ok());
+        assert_eq!(
+            resolve(&format!(
+                "{shape} class A impl Shape {{ area() {{ return 1; }} }} A();"
+            )),
+            Err(ResolverErr::MissingTraitMethod {
+                class: "A".to_owned(),
+                trait_name: "Shape".to_owned(),
+                method: "scale".to_owned(),
+                span: None
+            })
+        );
+        assert_eq!(
+            resolve(&format!(
+                "{shape} class A impl Shape {{ area(x) {{ return x; }} scale(f) {{ return f; }} }} A();"
+            )),
+            Err(ResolverErr::TraitArityMismatch {
+                class: "A".to_owned(),
+                trait_name: "Shape".to_owned(),
+                method: "area".to_owned(),
+                arity: 0,
+                span: None
+            })
+        );
+        assert_eq!(
+            resolve("class A impl Missing {} A();"),
+            Err(ResolverErr::UndefinedTrait("Missing".to_owned(), None))
+        );
+        assert_eq!(
+            resolve(&format!("{shape} {shape}")),
+            Err(ResolverErr::TraitRedefine("Shape".to_owned(), None))
+        );
+
+        // Inherited methods can't be checked if the superclass is only known at runtime
+        assert!(resolve(&format!(
+            "{shape} let a = f(); class B < a impl Shape {{}} B();"
+        ))
+        .is_ok());
+    }
+
+    #[test]
+    fn test_traits_with_shadowed_superclass() {
+        let area = "trait Area { area(); }";
+
+        // A class declared in an inner scope doesn't replace the outer one
+        assert!(resolve(&format!(
+            "{area} class A {{ area() {{ return 1; }} }} func f() {{ class A {{}} return A; }} class B < A impl Area {{}} B();"
+        ))
+        .is_ok());
+        assert_eq!(
+            resolve(&format!(
+                "{area} class A {{}} func f() {{ class A {{ area() {{ return 1; }} }} return A; }} class B < A impl Area {{}} B();"
+            )),
+            Err(ResolverErr::MissingTraitMethod {
+                class: "B".to_owned(),
+                trait_name: "Area".to_owned(),
+                method: "area".to_owned(),
+                span: None
+            })
+        );
+
+        // Inside the inner scope, the inner class is the one that's inherited from
+        assert!(resolve(&format!(
+            "{area} class A {{}} {{ class A {{ area() {{ return 1; }} }} class B < A impl Area {{}} B(); }}"
+        ))
+        .is_ok());
+    }
+
+    #[test]
+    fn test_traits_with_reassigned_superclass() {
+        let area = "trait Area { area(); }";
+
+        // Once the variable is reassigned, its methods are only known at runtime
+        assert!(resolve(&format!(
+            "{area} class A {{}} class Z {{ area() {{ return 1; }} }} A = Z; class B < A impl Area {{}} B();"
+        ))
+        .is_ok());
+        assert!(resolve(&format!(
+            "{area} class A {{}} class Z {{ area() {{ return 1; }} }} {{ A = Z; }} class B < A impl Area {{}} B();"
+        ))
+        .is_ok());
+    }
+
+    #[test]
+    fn test_match_bindings() {
+        assert_eq!(
+            resolve("match (1) { [x, x] => 1 };"),
+            Err(ResolverErr::VarRedefine("x".to_owned(), None))
+        );
+
+        // Every arm has its own scope
+        assert!(resolve("match (1) { [x] => x, x => x };").is_ok());
+    }
+
+    #[test]
+    fn test_variant_redefine() {
+        assert_eq!(
+            resolve("enum E { A, B, A(x) } E.B;"),
+            Err(ResolverErr::VariantRedefine {
+                enum_name: "E".to_owned(),
+                variant: "A".to_owned(),
+                span: None
+            })
+        );
+
+        // Fields can share names with variants
+        assert!(resolve("enum E { A(B), B } E.B;").is_ok());
+    }
+
+    #[test]
+    fn test_variant_field_count() {
+        assert_eq!(
+            resolve("enum E { A(x, y), B } let a = match (E.B) { E.A(x) => x, _ => 0 };"),
+            Err(ResolverErr::VariantFieldCount {
+                variant: "E.A".to_owned(),
+                expected: 2,
+                found: 1,
+                span: None
+            })
+        );
+
+        assert!(
+            resolve("enum E { A(x, y), B } let a = match (E.B) { E.A(x, y) => x, E.A => 0 };")
+                .is_ok()
+        );
+        // The enum a parameter holds isn't known until the code runs
+        assert!(resolve("func f(E) { let a = match (E.B) { E.A(x) => x }; } f(1);").is_ok());
+    }
+
+    #[test]
+    fn test_inheritance() {
+        assert_eq!(
+            resolve("class A < A {} A();"),
+            Err(ResolverErr::InheritFromSelf("A".to_owned(), None))
+        );
+        assert_eq!(
+            resolve("let n = 1; class A < n {} A();"),
+            Err(ResolverErr::InheritFromNonClass("n".to_owned(), None))
+        );
+        assert_eq!(
+            resolve("class A { f() { return super.f(); } }"),
+            Err(ResolverErr::SuperWithoutSuperclass(None))
+        );
+        assert_eq!(
+            resolve("func f() { return super.f(); }"),
+            Err(ResolverErr::SuperOutsideClass(None))
+        );
+
+        // Variables that might hold a class are left for the interpreter to check
+        assert!(resolve("class A {} let b = A; class B < b { f() { return super.f(); } }").is_ok());
+    }
+
+    #[test]
+    fn test_error_spans() {
+        let resolve_with_spans = |source: &str| {
+            let (tokens, spans) = Lexer::new(source).tokenize_with_spans().unwrap();
+            let mut stmts = Parser::with_spans(tokens, spans).get_statements().unwrap();
+
+            Resolver::new().resolve(&mut stmts).unwrap_err()
+        };
+
+        let err = resolve_with_spans("{\n  let a = 1;\n  let a = 2;\n}");
+        assert_eq!(
+            err.to_string(),
+            "Variable \"a\" already exists in this scope at line 3, column 7."
+        );
+
+        let err = resolve_with_spans("while (true) {}\nbreak;");
+        assert_eq!(
+            err.to_string(),
+            "Can't use \"break\" outside of a loop at line 2, column 1."
+        );
+
+        let err =
+            resolve_with_spans("class A {}\nclass B < A { f() { return this.x; } }\nprint this;");
+        assert_eq!(
+            err.to_string(),
+            "Can't use \"this\" outside of a class at line 3, column 7."
+        );
+    }
+}
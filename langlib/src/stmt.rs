@@ -1,15 +1,51 @@
-use super::expr::Expr;
+use super::{
+    class::{ClassDecl, TraitDecl},
+    enums::EnumDecl,
+    expr::{Expr, Var},
+    lexer::span::Span,
+    pattern::{Binding, Match, Pattern},
+};
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Stmt {
     Declaration(Declaration),
-    Assignment(Declaration),
+    /// Declares the variables bound by a list or map pattern (e.g `let [a, ...rest] = xs;`). The value has to
+    /// match the pattern. The variables are constants if the flag is set (e.g `const [a, b] = xs;`).
+    Destructure(Pattern, Expr, bool),
+    Assignment(Var, Expr),
+    /// Assigns to an element of a list (e.g `xs[i] = v`), with the list, index and value.
+    IndexAssignment(Expr, Expr, Expr),
+    /// Sets a property of an instance (e.g `this.x = v`), with the instance, property name and value.
+    SetProperty(Expr, String, Expr),
+    Class(ClassDecl),
+    /// A trait only matters to the resolver, so it does nothing at runtime.
+    Trait(TraitDecl),
+    Enum(EnumDecl),
+    Match(Match<Stmt>),
     Print(Expr),
     Expr(Expr),
     Block(Vec<Stmt>),
     If(Expr, Box<Stmt>, Option<Box<Stmt>>),
-    While(Expr, Box<Stmt>),
-    Return(Expr),
+    /// A loop with its condition and body. Loops desugared from `for` also carry an increment, which still runs
+    /// after a `continue`.
+    While(Expr, Box<Stmt>, Option<Box<Stmt>>),
+    ForIn(ForIn),
+    /// A `return`, with where the keyword is in the source. The same goes for `break` and `continue`.
+    Return(Expr, Option<Span>),
+    Break(Option<Span>),
+    Continue(Option<Span>),
+}
+
+/// A `for (x in iterable)` loop. With two variables (e.g `for (k, v in map)`), the loop binds the key and value
+/// of each entry of a map, or the index and element of anything else. The variables get their own scope, so they
+/// are always stored in the first slots of it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ForIn {
+    pub idents: Vec<String>,
+    pub iterable: Expr,
+    pub body: Box<Stmt>,
+    /// Where the loop variables are in the source, if the parser was given spans.
+    pub span: Option<Span>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
@@ -23,8 +59,28 @@ pub enum StmtErr {
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Declaration {
-    pub ident: String,
+    pub name: Binding,
     pub val: Expr,
+    /// Whether the variable was declared with `const`, so it can't be assigned to.
+    pub constant: bool,
+}
+
+impl Declaration {
+    pub fn new(ident: &str, val: Expr) -> Self {
+        Self {
+            name: Binding::new(ident),
+            val,
+            constant: false,
+        }
+    }
+
+    /// Creates a declaration of a constant (e.g `const x = 1;`).
+    pub fn new_const(ident: &str, val: Expr) -> Self {
+        Self {
+            constant: true,
+            ..Self::new(ident, val)
+        }
+    }
 }
 
 #[cfg(test)]
@@ -36,6 +92,7 @@ mod stmt_tests {
             token::{Keyword, Token},
         },
         parser::Parser,
+        pattern::Binding,
         stmt::Declaration,
     };
 
@@ -64,11 +121,13 @@ mod stmt_tests {
         assert_eq!(
             binding,
             Stmt::Declaration(Declaration {
-                ident: "coolVariable".to_owned(),
+                name: Binding::new("coolVariable"),
+                constant: false,
                 val: Expr::Bin(BinExpr {
                     lhs: Box::new(Expr::Num(1)),
                     rhs: Box::new(Expr::Num(1)),
-                    op: BinOp::Add
+                    op: BinOp::Add,
+                    span: None
                 })
             })
         )
@@ -97,11 +156,13 @@ mod stmt_tests {
         assert_eq!(
             binding,
             Stmt::Declaration(Declaration {
-                ident: "coolVariable".to_owned(),
+                name: Binding::new("coolVariable"),
+                constant: false,
                 val: Expr::Bin(BinExpr {
                     lhs: Box::new(Expr::Bool(true)),
                     rhs: Box::new(Expr::Bool(false)),
-                    op: BinOp::EqSign
+                    op: BinOp::EqSign,
+                    span: None
                 })
             })
         )
@@ -130,7 +191,8 @@ mod stmt_tests {
         assert_ne!(
             binding,
             Stmt::Declaration(Declaration {
-                ident: "coolVariable".to_owned(),
+                name: Binding::new("coolVariable"),
+                constant: false,
                 val: Expr::Num(3)
             })
         )
@@ -0,0 +1,529 @@
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+use colored::Colorize;
+
+use crate::{
+    bigint::BigInt,
+    class::{Class, Instance},
+    enums::{Constructor, Enum, Variant},
+    func::Closure,
+    interpreter::err::RuntimeErr,
+    map::{Key, Map},
+};
+
+/// A value produced while the interpreter runs. Unlike `Expr`, which describes the syntax of the code, a `Value`
+/// is always fully evaluated.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Int(i32),
+    /// An integer that doesn't fit in an `i32`. Integers that do fit are always stored as `Int`.
+    BigInt(BigInt),
+    Str(String),
+    Bool(bool),
+    Null,
+    Func(Closure),
+    /// A list. Lists are shared, so changes made through one reference to a list are seen through all of them.
+    List(Rc<RefCell<Vec<Value>>>),
+    /// A map from keys to values, which is shared the same way lists are.
+    Map(Rc<RefCell<Map>>),
+    Range(Range),
+    Class(Rc<Class>),
+    /// An instance of a class. Instances are shared, so changes to their fields are seen through every reference.
+    Instance(Rc<RefCell<Instance>>),
+    Enum(Rc<Enum>),
+    /// A function that makes values of a variant of an enum (e.g `Shape.Circle`).
+    Constructor(Constructor),
+    /// A value of a variant of an enum (e.g `Shape.Circle(2)`).
+    Variant(Rc<Variant>),
+}
+
+impl Value {
+    /// Creates a new list.
+    pub fn list(items: Vec<Value>) -> Self {
+        Value::List(Rc::new(RefCell::new(items)))
+    }
+
+    /// Creates a new map.
+    pub fn map(map: Map) -> Self {
+        Value::Map(Rc::new(RefCell::new(map)))
+    }
+
+    /// Returns the type tag of the value.
+    pub fn type_of(&self) -> LexerThingType {
+        match self {
+            Value::Int(_) | Value::BigInt(_) => LexerThingType::Int,
+            Value::Str(_) => LexerThingType::Str,
+            Value::Bool(_) => LexerThingType::Bool,
+            Value::Null => LexerThingType::Null,
+            Value::Func(_) => LexerThingType::Func,
+            Value::List(_) => LexerThingType::List,
+            Value::Map(_) => LexerThingType::Map,
+            Value::Range(_) => LexerThingType::Range,
+            Value::Class(_) => LexerThingType::Class,
+            Value::Instance(_) => LexerThingType::Instance,
+            Value::Enum(_) => LexerThingType::Enum,
+            // Constructors are called just like functions
+            Value::Constructor(_) => LexerThingType::Func,
+            Value::Variant(_) => LexerThingType::Variant,
+        }
+    }
+
+    /// Gets a property of an instance, a variant of an enum, or one of the values held by a variant.
+    pub fn get_property(&self, ident: &str) -> Result<Value, RuntimeErr> {
+        match self {
+            Value::Instance(instance) => instance.borrow().get(ident, self),
+            Value::Enum(enum_val) => Enum::get(enum_val, ident),
+            Value::Variant(variant) => variant.get(ident),
+            Value::Null => Err(RuntimeErr::NullProperty(ident.to_owned())),
+            _ => Err(RuntimeErr::NoProperties(self.type_of())),
+        }
+    }
+
+    /// Sets a field of an instance.
+    pub fn set_property(&self, ident: &str, v: Value) -> Result<(), RuntimeErr> {
+        match self {
+            Value::Instance(instance) => {
+                instance.borrow_mut().set(ident, v);
+
+                Ok(())
+            }
+            Value::Null => Err(RuntimeErr::NullProperty(ident.to_owned())),
+            _ => Err(RuntimeErr::NoProperties(self.type_of())),
+        }
+    }
+
+    /// Gets the element at `index`, or the value stored under the key `index` for maps. Negative indices count
+    /// from the end.
+    pub fn get_index(&self, index: &Value) -> Result<Value, RuntimeErr> {
+        match self {
+            Value::List(items) => {
+                let items = items.borrow();
+
+                Ok(items[Value::resolve_index(index, items.len())?].clone())
+            }
+            Value::Map(map) => {
+                let key = Key::try_from(index)?;
+
+                match map.borrow().get(&key) {
+                    Some(v) => Ok(v.clone()),
+                    None => Err(RuntimeErr::KeyNotFound(key)),
+                }
+            }
+            _ => Err(RuntimeErr::NotIndexable(self.type_of())),
+        }
+    }
+
+    /// Replaces the element at `index`, or stores a value under the key `index` for maps. Negative indices count
+    /// from the end.
+    pub fn set_index(&self, index: &Value, v: Value) -> Result<(), RuntimeErr> {
+        match self {
+            Value::List(items) => {
+                let mut items = items.borrow_mut();
+                let index = Value::resolve_index(index, items.len())?;
+
+                items[index] = v;
+
+                Ok(())
+            }
+            Value::Map(map) => {
+                map.borrow_mut().insert(Key::try_from(index)?, v);
+
+                Ok(())
+            }
+            _ => Err(RuntimeErr::NotIndexable(self.type_of())),
+        }
+    }
+
+    /// Checks if `item` is a key of this map, an element of this list, or a substring of this string.
+    pub fn contains(&self, item: &Value) -> Result<bool, RuntimeErr> {
+        match (self, item) {
+            (Value::Map(map), item) => Ok(map.borrow().contains_key(&Key::try_from(item)?)),
+            (Value::List(items), item) => Ok(items.borrow().contains(item)),
+            (Value::Str(string), Value::Str(item)) => Ok(string.contains(item.as_str())),
+            (Value::Range(range), Value::Int(item)) => Ok(range.bounds().contains(&(*item as i64))),
+            (Value::Range(_), Value::BigInt(_)) => Ok(false),
+            (Value::Range(_), _) => Err(RuntimeErr::UnexpectedType(LexerThingType::Int)),
+            (Value::Str(_), _) => Err(RuntimeErr::UnexpectedType(LexerThingType::Str)),
+            _ => Err(RuntimeErr::NotIndexable(self.type_of())),
+        }
+    }
+
+    /// Returns an iterator over the entries of the value. Each entry is the key and value for maps, or the index
+    /// and element for everything else.
+    pub fn iter(&self) -> Result<Box<dyn Iterator<Item = (Value, Value)>>, RuntimeErr> {
+        let iter: Box<dyn Iterator<Item = (Value, Value)>> = match self {
+            // Lists are read one element at a time, so the loop sees any changes made to the list while it runs
+            Value::List(items) => {
+                let items = Rc::clone(items);
+
+                Box::new((0..).map_while(move |i: i64| {
+                    let item = items.borrow().get(i as usize).cloned()?;
+
+                    Some((Value::from(i), item))
+                }))
+            }
+            Value::Map(map) => {
+                let entries: Vec<_> = map
+                    .borrow()
+                    .iter()
+                    .map(|(k, v)| (Value::from(k.clone()), v.clone()))
+                    .collect();
+
+                Box::new(entries.into_iter())
+            }
+            Value::Str(string) => {
+                let chars: Vec<_> = string.chars().map(|c| Value::Str(c.to_string())).collect();
+
+                Box::new(
+                    chars
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, c)| (Value::from(i as i64), c)),
+                )
+            }
+            Value::Range(range) => {
+                let start = range.bounds().start;
+
+                Box::new(
+                    range
+                        .bounds()
+                        .map(move |i| (Value::from(i - start), Value::from(i))),
+                )
+            }
+            _ => return Err(RuntimeErr::NotIterable(self.type_of())),
+        };
+
+        Ok(iter)
+    }
+
+    /// Copies the elements from `start` up to `end` into a new list. Like indices, the bounds can be negative, and
+    /// bounds that go past either end of the list are clamped to it.
+    pub fn slice(&self, start: Option<&Value>, end: Option<&Value>) -> Result<Value, RuntimeErr> {
+        match self {
+            Value::List(items) => {
+                let items = items.borrow();
+
+                let start = Value::resolve_bound(start, items.len(), 0)?;
+                let end = Value::resolve_bound(end, items.len(), items.len())?;
+
+                Ok(Value::list(items[start..end.max(start)].to_vec()))
+            }
+            _ => Err(RuntimeErr::NotIndexable(self.type_of())),
+        }
+    }
+
+    /// Turns an index into a position in a collection of length `len`.
+    fn resolve_index(index: &Value, len: usize) -> Result<usize, RuntimeErr> {
+        match index {
+            Value::Int(i) => {
+                let pos = if *i < 0 {
+                    *i as i64 + len as i64
+                } else {
+                    *i as i64
+                };
+
+                match usize::try_from(pos) {
+                    Ok(pos) if pos < len => Ok(pos),
+                    _ => Err(RuntimeErr::IndexOutOfBounds(BigInt::from(*i), len)),
+                }
+            }
+            // A big integer is never a valid index
+            Value::BigInt(i) => Err(RuntimeErr::IndexOutOfBounds(i.clone(), len)),
+            _ => Err(RuntimeErr::UnexpectedType(LexerThingType::Int)),
+        }
+    }
+
+    /// Turns a slice bound into a position in a collection of length `len`, using `default` if it was left out.
+    fn resolve_bound(
+        bound: Option<&Value>,
+        len: usize,
+        default: usize,
+    ) -> Result<usize, RuntimeErr> {
+        match bound {
+            None => Ok(default),
+            Some(Value::Int(i)) => {
+                let pos = if *i < 0 {
+                    *i as i64 + len as i64
+                } else {
+                    *i as i64
+                };
+
+                Ok(pos.clamp(0, len as i64) as usize)
+            }
+            Some(Value::BigInt(i)) if i.is_negative() => Ok(0),
+            Some(Value::BigInt(_)) => Ok(len),
+            Some(_) => Err(RuntimeErr::UnexpectedType(LexerThingType::Int)),
+        }
+    }
+
+    /// Writes the value, writing `[...]` or `{...}` for a list or map that contains itself. `seen` holds the lists
+    /// and maps that are currently being written.
+    pub(crate) fn fmt_seen(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        seen: &mut Vec<*const ()>,
+    ) -> std::fmt::Result {
+        match self {
+            Value::List(items) => {
+                let ptr = Rc::as_ptr(items) as *const ();
+
+                if seen.contains(&ptr) {
+                    return write!(f, "[...]");
+                }
+
+                seen.push(ptr);
+                write!(f, "[")?;
+
+                for (i, item) in items.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    item.fmt_seen(f, seen)?;
+                }
+
+                seen.pop();
+                write!(f, "]")
+            }
+            Value::Map(map) => {
+                let ptr = Rc::as_ptr(map) as *const ();
+
+                if seen.contains(&ptr) {
+                    return write!(f, "{{...}}");
+                }
+
+                seen.push(ptr);
+                write!(f, "{{")?;
+
+                for (i, (k, v)) in map.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{}: ", Value::from(k.clone()))?;
+                    v.fmt_seen(f, seen)?;
+                }
+
+                seen.pop();
+                write!(f, "}}")
+            }
+            Value::Variant(variant) => variant.fmt_seen(f, seen),
+            v => write!(f, "{v}"),
+        }
+    }
+
+    /// Compares two values. A pair of lists or maps that is already being compared further up is taken to be
+    /// equal, so values that contain themselves can be compared without looping forever.
+    fn eq_seen(&self, other: &Value, seen: &mut Vec<(*const (), *const ())>) -> bool {
+        match (self, other) {
+            (Value::List(a), Value::List(b)) => {
+                let pair = (Rc::as_ptr(a) as *const (), Rc::as_ptr(b) as *const ());
+
+                if Rc::ptr_eq(a, b) || seen.contains(&pair) {
+                    return true;
+                }
+
+                let (a, b) = (a.borrow(), b.borrow());
+
+                seen.push(pair);
+                let eq =
+                    a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| a.eq_seen(b, seen));
+                seen.pop();
+
+                eq
+            }
+            // Maps are equal if they have the same entries, no matter what order they were inserted in
+            (Value::Map(a), Value::Map(b)) => {
+                let pair = (Rc::as_ptr(a) as *const (), Rc::as_ptr(b) as *const ());
+
+                if Rc::ptr_eq(a, b) || seen.contains(&pair) {
+                    return true;
+                }
+
+                let (a, b) = (a.borrow(), b.borrow());
+
+                seen.push(pair);
+                let eq = a.len() == b.len()
+                    && a.iter()
+                        .all(|(k, v)| b.get(k).is_some_and(|other| v.eq_seen(other, seen)));
+                seen.pop();
+
+                eq
+            }
+            (Value::Variant(a), Value::Variant(b)) => {
+                Rc::ptr_eq(a, b)
+                    || (a.enum_val == b.enum_val
+                        && a.tag == b.tag
+                        && a.values.len() == b.values.len()
+                        && a.values
+                            .iter()
+                            .zip(b.values.iter())
+                            .all(|(a, b)| a.eq_seen(b, seen)))
+            }
+            (Value::Int(a), Value::Int(b)) => a == b,
+            (Value::BigInt(a), Value::BigInt(b)) => a == b,
+            (Value::Str(a), Value::Str(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Null, Value::Null) => true,
+            (Value::Func(a), Value::Func(b)) => a == b,
+            (Value::Range(a), Value::Range(b)) => a == b,
+            (Value::Class(a), Value::Class(b)) => a == b,
+            (Value::Instance(a), Value::Instance(b)) => a == b,
+            (Value::Enum(a), Value::Enum(b)) => a == b,
+            (Value::Constructor(a), Value::Constructor(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Returns whether the value counts as true in a condition, or `None` if it can't be used as one.
+    pub fn truthy(&self) -> Option<bool> {
+        match self {
+            Value::Bool(bool) => Some(*bool),
+            Value::Int(num) => Some(*num > 0),
+            Value::BigInt(num) => Some(!num.is_negative()),
+            Value::Str(s) => Some(!s.is_empty()),
+            Value::Null => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_seen(other, &mut Vec::new())
+    }
+}
+
+impl Eq for Value {}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Int(num) => write!(f, "{}", format!("{num}").yellow()),
+            Value::BigInt(num) => write!(f, "{}", format!("{num}").yellow()),
+
+            Value::Str(string) => write!(f, "{string}"),
+
+            Value::Bool(bool) => write!(f, "{}", format!("{bool}").yellow()),
+            Value::Null => write!(f, "{}", "null".bright_black()),
+            Value::Func(_) => write!(f, "{}", "<func>".bright_black()),
+            Value::List(_) | Value::Map(_) | Value::Variant(_) => self.fmt_seen(f, &mut Vec::new()),
+            Value::Range(range) => write!(f, "{range}"),
+            Value::Class(class) => {
+                write!(f, "{}", format!("<class {}>", class.name).bright_black())
+            }
+            Value::Instance(instance) => write!(
+                f,
+                "{}",
+                format!("<{} instance>", instance.borrow().class.name).bright_black()
+            ),
+            Value::Enum(enum_val) => {
+                write!(f, "{}", format!("<enum {}>", enum_val.name).bright_black())
+            }
+            Value::Constructor(constructor) => write!(
+                f,
+                "{}",
+                format!("<constructor {}>", constructor.decl().ident).bright_black()
+            ),
+        }
+    }
+}
+
+impl From<BigInt> for Value {
+    /// Stores the integer as an `Int` if it fits in one.
+    fn from(num: BigInt) -> Self {
+        match num.to_i32() {
+            Some(num) => Value::Int(num),
+            None => Value::BigInt(num),
+        }
+    }
+}
+
+impl From<i64> for Value {
+    /// Stores the integer as an `Int` if it fits in one.
+    fn from(num: i64) -> Self {
+        match i32::try_from(num) {
+            Ok(num) => Value::Int(num),
+            Err(_) => Value::BigInt(BigInt::from(num)),
+        }
+    }
+}
+
+impl TryInto<i32> for Value {
+    type Error = RuntimeErr;
+
+    fn try_into(self) -> Result<i32, Self::Error> {
+        match self {
+            Value::Int(num) => Ok(num),
+            _ => Err(RuntimeErr::UnexpectedType(LexerThingType::Int)),
+        }
+    }
+}
+
+impl TryInto<bool> for Value {
+    type Error = RuntimeErr;
+
+    fn try_into(self) -> Result<bool, Self::Error> {
+        self.truthy()
+            .ok_or(RuntimeErr::UnexpectedType(LexerThingType::Bool))
+    }
+}
+
+impl TryInto<String> for Value {
+    type Error = RuntimeErr;
+
+    fn try_into(self) -> Result<String, Self::Error> {
+        match self {
+            Value::Str(s) => Ok(s),
+            _ => Err(RuntimeErr::UnexpectedType(LexerThingType::Str)),
+        }
+    }
+}
+
+/// A range of integers.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Range {
+    pub start: i32,
+    pub end: i32,
+    /// Whether `end` is part of the range.
+    pub inclusive: bool,
+}
+
+impl Range {
+    /// Returns the range as a half-open Rust range. The bounds are widened so an inclusive range that ends at the
+    /// largest `i32` doesn't overflow.
+    pub fn bounds(&self) -> std::ops::Range<i64> {
+        self.start as i64..self.end as i64 + self.inclusive as i64
+    }
+}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let op = if self.inclusive { "..=" } else { ".." };
+
+        write!(f, "{}{op}{}", self.start, self.end)
+    }
+}
+
+/// The type of a `Value`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LexerThingType {
+    Int,
+    Str,
+    Bool,
+    Null,
+    Func,
+    List,
+    Map,
+    Range,
+    Class,
+    Instance,
+    Enum,
+    Variant,
+}
+
+impl Display for LexerThingType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}